@@ -1,4 +1,6 @@
-use intspan::IntSpan;
+use intspan::{bin2set_m, set2bin, set2yaml_m, yaml2set_m, Bitmap, IntSpan};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
 use std::env;
 use std::time::Instant;
 
@@ -49,9 +51,144 @@ fn run_benchmark() {
     }
 }
 
+// Sets with many islands, like repeat masks on whole chromosomes
+fn run_set_ops() {
+    let islands = 20_000;
+
+    let mut set_a = IntSpan::new();
+    let mut set_b = IntSpan::new();
+    for i in 0..islands {
+        set_a.add_pair(i * 10 + 1, i * 10 + 5);
+        set_b.add_pair(i * 10 + 4, i * 10 + 8);
+    }
+
+    // Previous path, built on add_pair() and vector insertions
+    fn add_by_pairs(set: &mut IntSpan, other: &IntSpan) {
+        for (lower, upper) in other.spans() {
+            set.add_pair(lower, upper);
+        }
+    }
+
+    fn union_by_pairs(a: &IntSpan, b: &IntSpan) -> IntSpan {
+        let mut new = a.copy();
+        add_by_pairs(&mut new, b);
+        new
+    }
+
+    fn intersect_by_pairs(a: &IntSpan, b: &IntSpan) -> IntSpan {
+        let mut new = a.complement();
+        add_by_pairs(&mut new, &b.complement());
+        new.invert();
+        new
+    }
+
+    println!("set ops on {} islands", islands);
+    for (name, by_pairs, by_sweep) in [
+        (
+            "union",
+            union_by_pairs as fn(&IntSpan, &IntSpan) -> IntSpan,
+            IntSpan::union as fn(&IntSpan, &IntSpan) -> IntSpan,
+        ),
+        ("intersect", intersect_by_pairs, IntSpan::intersect),
+    ] {
+        let start = Instant::now();
+        let expected = by_pairs(&set_a, &set_b);
+        let elapsed_pairs = start.elapsed();
+
+        let start = Instant::now();
+        let result = by_sweep(&set_a, &set_b);
+        let elapsed_sweep = start.elapsed();
+
+        assert_eq!(result, expected);
+        println!(
+            "{}: add_pair {} s, sweep {} s",
+            name,
+            elapsed_pairs.as_secs_f64(),
+            elapsed_sweep.as_secs_f64()
+        );
+    }
+}
+
+fn run_loading() {
+    let mut set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = BTreeMap::new();
+    for name in 0..10 {
+        let mut set: BTreeMap<String, IntSpan> = BTreeMap::new();
+        for chr in 0..16 {
+            let mut intspan = IntSpan::new();
+            for i in 0..2_000 {
+                intspan.add_pair(i * 100 + name + chr, i * 100 + 50);
+            }
+            set.insert(format!("chr{}", chr), intspan);
+        }
+        set_of.insert(format!("name{}", name), set);
+    }
+
+    let yaml = serde_yaml::to_string(&set2yaml_m(&set_of)).unwrap();
+    let bin = set2bin(&set_of);
+    println!(
+        "loading, YAML {} bytes, binary {} bytes",
+        yaml.len(),
+        bin.len()
+    );
+
+    let start = Instant::now();
+    let value: BTreeMap<String, Value> = serde_yaml::from_str(&yaml).unwrap();
+    let from_yaml: BTreeMap<String, BTreeMap<String, IntSpan>> = yaml2set_m(&value).unwrap();
+    let elapsed_yaml = start.elapsed();
+
+    let start = Instant::now();
+    let from_bin: BTreeMap<String, BTreeMap<String, IntSpan>> = bin2set_m(&bin).unwrap();
+    let elapsed_bin = start.elapsed();
+
+    assert_eq!(from_yaml, from_bin);
+    println!(
+        "YAML {} s, binary {} s",
+        elapsed_yaml.as_secs_f64(),
+        elapsed_bin.as_secs_f64()
+    );
+}
+
+fn run_bitmap() {
+    // scattered positions, as in SNP calls
+    let mut snps = IntSpan::new();
+    let mut n = 1;
+    while n < 2_000_000 {
+        snps.add_n(n);
+        n += 7;
+    }
+    let mut exons = IntSpan::new();
+    for i in 0..2_000 {
+        exons.add_pair(i * 1000 + 1, i * 1000 + 300);
+    }
+    let bitmap = Bitmap::from(&snps);
+    println!(
+        "intersect {} SNPs with {} exons",
+        snps.cardinality(),
+        exons.span_size()
+    );
+
+    let start = Instant::now();
+    let expected = snps.intersect(&exons);
+    let elapsed_spans = start.elapsed();
+
+    let start = Instant::now();
+    let result = bitmap.intersect_intspan(&exons);
+    let elapsed_bitmap = start.elapsed();
+
+    assert_eq!(result.to_intspan(), expected);
+    println!(
+        "IntSpan {} s, Bitmap {} s",
+        elapsed_spans.as_secs_f64(),
+        elapsed_bitmap.as_secs_f64()
+    );
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     println!("{:?}", args);
 
     run_benchmark();
+    run_set_ops();
+    run_loading();
+    run_bitmap();
 }
@@ -0,0 +1,520 @@
+//! A compact binary container for runlist files.
+//!
+//! Parsing large YAML runlists dominates the runtime of commands like `intspan stat`. The binary
+//! container holds the same single- or multi-name sets, with edges delta and varint encoded.
+//!
+//! ```text
+//! magic       b"ISPB"
+//! version     u8, currently 1
+//! flags       u8, bit 0 is set for multi-name files
+//! sections    varint count, then for each section
+//!     name        string, empty in single-name files
+//!     chrs        varint count, then for each chromosome
+//!         chr         string
+//!         spans       varint, number of spans
+//!         length      varint, bytes of its edge block
+//!     blocks      edge blocks, in the order of the index
+//! ```
+//!
+//! Varints are unsigned LEB128 and strings are varint lengths followed by UTF-8 bytes. An edge block
+//! holds the first edge zigzag encoded, then the positive differences between consecutive edges.
+//! The per-chromosome index lets `BinIndex` decode one set without touching the others.
+//!
+//! ```
+//! use intspan::*;
+//! use std::collections::BTreeMap;
+//!
+//! let yaml = read_yaml("tests/resources/intergenic.yml").unwrap();
+//! let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = yaml2set_m(&yaml).unwrap();
+//!
+//! let bytes = set2bin(&set_of);
+//! assert!(is_bin(&bytes));
+//! assert_eq!(bin2set_m::<i32>(&bytes).unwrap(), set_of);
+//!
+//! let index = BinIndex::new(&bytes).unwrap();
+//! assert!(!index.is_multi());
+//! let set: IntSpan = index.get("__single", "I").unwrap().unwrap();
+//! assert_eq!(set.to_string(), set_of["__single"]["I"].to_string());
+//! ```
+
+use crate::{Coord, GenericIntSpan};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Leading bytes of the binary container
+pub const BIN_MAGIC: &[u8; 4] = b"ISPB";
+
+/// Version of the binary container written by `set2bin()`
+pub const BIN_VERSION: u8 = 1;
+
+const FLAG_MULTI: u8 = 1;
+
+/// Name of the only section of single-name files, as in `yaml2set_m()`
+const SINGLE: &str = "__single";
+
+/// True if `bytes` starts with the magic bytes of the binary container
+///
+/// ```
+/// assert!(intspan::is_bin(b"ISPB\x01\x00\x00"));
+/// assert!(!intspan::is_bin(b"---\nI: 1-100\n"));
+/// ```
+pub fn is_bin(bytes: &[u8]) -> bool {
+    bytes.starts_with(BIN_MAGIC)
+}
+
+/// Encodes sets keyed by name then chromosome. A lone `__single` name, as returned by
+/// `yaml2set_m()` on single-name files, is written as a single-name file
+pub fn set2bin<T: Coord>(
+    set_of: &BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
+) -> Vec<u8> {
+    let is_multi = !(set_of.len() == 1 && set_of.contains_key(SINGLE));
+
+    let mut bytes: Vec<u8> = BIN_MAGIC.to_vec();
+    bytes.push(BIN_VERSION);
+    bytes.push(if is_multi { FLAG_MULTI } else { 0 });
+
+    put_varint(&mut bytes, set_of.len() as u128);
+    for (name, set) in set_of {
+        put_str(&mut bytes, if is_multi { name } else { "" });
+
+        let mut blocks: Vec<u8> = vec![];
+        put_varint(&mut bytes, set.len() as u128);
+        for (chr, intspan) in set {
+            let start = blocks.len();
+            let mut prev: Option<i128> = None;
+            for (lower, upper) in intspan.spans() {
+                for edge in [lower.to_i128(), upper.to_i128() + 1] {
+                    match prev {
+                        None => put_varint(&mut blocks, zigzag(edge)),
+                        Some(p) => put_varint(&mut blocks, (edge - p) as u128),
+                    }
+                    prev = Some(edge);
+                }
+            }
+
+            put_str(&mut bytes, chr);
+            put_varint(&mut bytes, intspan.span_size() as u128);
+            put_varint(&mut bytes, (blocks.len() - start) as u128);
+        }
+        bytes.extend(blocks);
+    }
+
+    bytes
+}
+
+/// Decodes the whole container. Single-name files are keyed by `__single`, as in `yaml2set_m()`
+pub fn bin2set_m<T: Coord>(
+    bytes: &[u8],
+) -> Result<BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>, BinError> {
+    let index = BinIndex::new(bytes)?;
+
+    let mut s_of: BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>> = BTreeMap::new();
+    for section in &index.sections {
+        let mut set: BTreeMap<String, GenericIntSpan<T>> = BTreeMap::new();
+        for entry in &section.chrs {
+            set.insert(entry.chr.clone(), index.decode(entry)?);
+        }
+        s_of.insert(section.name.clone(), set);
+    }
+
+    Ok(s_of)
+}
+
+/// The parsed header of a binary container, decoding sets on demand
+pub struct BinIndex<'a> {
+    bytes: &'a [u8],
+    is_multi: bool,
+    sections: Vec<BinSection>,
+}
+
+struct BinSection {
+    name: String,
+    chrs: Vec<BinEntry>,
+}
+
+struct BinEntry {
+    chr: String,
+    spans: usize,
+    offset: usize,
+    length: usize,
+}
+
+impl<'a> BinIndex<'a> {
+    /// Reads the header and the per-chromosome index, leaving edge blocks untouched
+    pub fn new(bytes: &'a [u8]) -> Result<Self, BinError> {
+        if !is_bin(bytes) {
+            return Err(BinError::new(BinErrorKind::BadMagic, 0));
+        }
+        let mut cursor = Cursor {
+            bytes,
+            pos: BIN_MAGIC.len(),
+        };
+
+        let version = cursor.byte()?;
+        if version != BIN_VERSION {
+            return Err(BinError::new(
+                BinErrorKind::UnsupportedVersion,
+                cursor.pos - 1,
+            ));
+        }
+        let is_multi = cursor.byte()? & FLAG_MULTI == FLAG_MULTI;
+
+        let mut sections = vec![];
+        for _ in 0..cursor.len()? {
+            let mut name = cursor.str()?;
+            if !is_multi {
+                name = SINGLE.to_string();
+            }
+
+            let mut chrs = vec![];
+            for _ in 0..cursor.len()? {
+                chrs.push(BinEntry {
+                    chr: cursor.str()?,
+                    spans: cursor.len()?,
+                    offset: 0,
+                    length: cursor.len()?,
+                });
+            }
+
+            // blocks follow the index of their section
+            for entry in &mut chrs {
+                entry.offset = cursor.pos;
+                cursor.skip(entry.length)?;
+            }
+            sections.push(BinSection { name, chrs });
+        }
+
+        if cursor.pos != bytes.len() {
+            return Err(BinError::new(BinErrorKind::Truncated, cursor.pos));
+        }
+
+        Ok(Self {
+            bytes,
+            is_multi,
+            sections,
+        })
+    }
+
+    pub fn is_multi(&self) -> bool {
+        self.is_multi
+    }
+
+    /// Names of sections, `__single` for single-name files
+    pub fn names(&self) -> Vec<&str> {
+        self.sections.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Chromosomes of a section, empty if there is no such name
+    pub fn chrs(&self, name: &str) -> Vec<&str> {
+        self.section(name)
+            .map(|s| s.chrs.iter().map(|e| e.chr.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Decodes the set of `chr` in section `name`, `None` if there is no such set
+    pub fn get<T: Coord>(
+        &self,
+        name: &str,
+        chr: &str,
+    ) -> Option<Result<GenericIntSpan<T>, BinError>> {
+        self.section(name)
+            .and_then(|s| s.chrs.iter().find(|e| e.chr == chr))
+            .map(|entry| self.decode(entry))
+    }
+
+    fn section(&self, name: &str) -> Option<&BinSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    fn decode<T: Coord>(&self, entry: &BinEntry) -> Result<GenericIntSpan<T>, BinError> {
+        let mut cursor = Cursor {
+            bytes: &self.bytes[..entry.offset + entry.length],
+            pos: entry.offset,
+        };
+
+        let mut pairs: Vec<(T, T)> = Vec::with_capacity(entry.spans);
+        let mut prev: Option<i128> = None;
+        for _ in 0..entry.spans {
+            let mut span = [T::ZERO; 2];
+            for edge in &mut span {
+                let start = cursor.pos;
+                let n = match prev {
+                    None => unzigzag(cursor.varint()?),
+                    Some(p) => {
+                        let delta = cursor.varint()?;
+                        if delta == 0 {
+                            return Err(BinError::new(BinErrorKind::BadOrder, start));
+                        }
+                        i128::try_from(delta)
+                            .ok()
+                            .and_then(|d| p.checked_add(d))
+                            .ok_or_else(|| BinError::new(BinErrorKind::Overflow, start))?
+                    }
+                };
+                *edge = T::from_i128(n)
+                    .filter(|&e| e >= T::NEG_INF && e <= T::POS_INF)
+                    .ok_or_else(|| BinError::new(BinErrorKind::Overflow, start))?;
+                prev = Some(n);
+            }
+            pairs.push((span[0], span[1] - T::ONE));
+        }
+
+        if cursor.pos != cursor.bytes.len() {
+            return Err(BinError::new(BinErrorKind::Truncated, cursor.pos));
+        }
+
+        // already sorted and disjoint, so this is linear
+        Ok(GenericIntSpan::from_pairs(pairs))
+    }
+}
+
+//----------------------------------------------------------
+// Varints
+//----------------------------------------------------------
+
+fn put_varint(bytes: &mut Vec<u8>, mut n: u128) {
+    while n >= 0x80 {
+        bytes.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+fn put_str(bytes: &mut Vec<u8>, s: &str) {
+    put_varint(bytes, s.len() as u128);
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+fn zigzag(n: i128) -> u128 {
+    ((n << 1) ^ (n >> 127)) as u128
+}
+
+fn unzigzag(n: u128) -> i128 {
+    ((n >> 1) as i128) ^ -((n & 1) as i128)
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn byte(&mut self) -> Result<u8, BinError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| BinError::new(BinErrorKind::Truncated, self.pos))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u128, BinError> {
+        let start = self.pos;
+        let mut n: u128 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= 128 || (shift == 126 && byte > 0b11) {
+                return Err(BinError::new(BinErrorKind::Overflow, start));
+            }
+            n |= ((byte & 0x7f) as u128) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+            shift += 7;
+        }
+    }
+
+    /// A count or a length, which must fit in the remaining bytes to be sane
+    fn len(&mut self) -> Result<usize, BinError> {
+        let start = self.pos;
+        let n = self.varint()?;
+        if n > self.bytes.len() as u128 {
+            return Err(BinError::new(BinErrorKind::Truncated, start));
+        }
+        Ok(n as usize)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), BinError> {
+        if self.pos + n > self.bytes.len() {
+            return Err(BinError::new(BinErrorKind::Truncated, self.pos));
+        }
+        self.pos += n;
+        Ok(())
+    }
+
+    fn str(&mut self) -> Result<String, BinError> {
+        let n = self.len()?;
+        let start = self.pos;
+        self.skip(n)?;
+        String::from_utf8(self.bytes[start..self.pos].to_vec())
+            .map_err(|_| BinError::new(BinErrorKind::InvalidUtf8, start))
+    }
+}
+
+//----------------------------------------------------------
+// BinError
+//----------------------------------------------------------
+
+/// The kind of error encountered while decoding a binary container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinErrorKind {
+    /// The leading bytes are not `BIN_MAGIC`
+    BadMagic,
+    /// A version other than `BIN_VERSION`
+    UnsupportedVersion,
+    /// The data ends in the middle of a field, or has trailing bytes
+    Truncated,
+    /// A varint or an edge beyond the coordinate type
+    Overflow,
+    /// Edges not strictly increasing
+    BadOrder,
+    /// A name or chromosome not in UTF-8
+    InvalidUtf8,
+}
+
+/// Error returned by `bin2set_m()` and `BinIndex`
+///
+/// ```
+/// # use intspan::{bin2set_m, BinErrorKind};
+/// let err = bin2set_m::<i32>(b"ISPB\x02").err().unwrap();
+/// assert_eq!(err.kind(), BinErrorKind::UnsupportedVersion);
+/// assert_eq!(err.offset(), 4);
+/// assert_eq!(err.to_string(), "Unsupported version of binary runlists at byte 4");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinError {
+    kind: BinErrorKind,
+    offset: usize,
+}
+
+impl BinError {
+    fn new(kind: BinErrorKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+
+    pub fn kind(&self) -> BinErrorKind {
+        self.kind
+    }
+
+    /// Byte offset where decoding failed
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for BinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            BinErrorKind::BadMagic => "Not binary runlists",
+            BinErrorKind::UnsupportedVersion => "Unsupported version of binary runlists",
+            BinErrorKind::Truncated => "Truncated binary runlists",
+            BinErrorKind::Overflow => "Number overflow in binary runlists",
+            BinErrorKind::BadOrder => "Bad order of edges in binary runlists",
+            BinErrorKind::InvalidUtf8 => "Invalid UTF-8 in binary runlists",
+        };
+        write!(f, "{} at byte {}", what, self.offset)
+    }
+}
+
+impl std::error::Error for BinError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{yaml2set_m, IntSpan, IntSpan64};
+
+    fn single(pairs: &[(&str, &str)]) -> BTreeMap<String, BTreeMap<String, IntSpan>> {
+        let mut set: BTreeMap<String, IntSpan> = BTreeMap::new();
+        for (chr, runlist) in pairs {
+            set.insert(chr.to_string(), IntSpan::from(*runlist));
+        }
+        let mut s_of = BTreeMap::new();
+        s_of.insert(SINGLE.to_string(), set);
+        s_of
+    }
+
+    #[test]
+    fn test_varint() {
+        for &n in &[0u128, 1, 127, 128, 300, u64::MAX as u128, u128::MAX] {
+            let mut bytes = vec![];
+            put_varint(&mut bytes, n);
+            let mut cursor = Cursor {
+                bytes: &bytes,
+                pos: 0,
+            };
+            assert_eq!(cursor.varint().unwrap(), n);
+            assert_eq!(cursor.pos, bytes.len());
+        }
+
+        for &n in &[0i128, 1, -1, i32::MIN as i128, i64::MAX as i128] {
+            assert_eq!(unzigzag(zigzag(n)), n);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let s_of = single(&[("I", "1-100,201-300,1000"), ("II", "-"), ("III", "-5--1,3")]);
+        let bytes = set2bin(&s_of);
+        assert_eq!(bin2set_m::<i32>(&bytes).unwrap(), s_of);
+
+        // tests/resources/I.II.yml, with two names
+        let yaml = crate::read_yaml("tests/resources/I.II.yml").unwrap();
+        let s_of: BTreeMap<String, BTreeMap<String, IntSpan>> = yaml2set_m(&yaml).unwrap();
+        let bytes = set2bin(&s_of);
+        assert_eq!(bin2set_m::<i32>(&bytes).unwrap(), s_of);
+
+        let index = BinIndex::new(&bytes).unwrap();
+        assert!(index.is_multi());
+        assert_eq!(index.names(), vec!["I", "II"]);
+        assert!(index.get::<i32>("III", "I").is_none());
+
+        // infinite sets and wide coordinates
+        let mut set: BTreeMap<String, IntSpan64> = BTreeMap::new();
+        set.insert("I".to_string(), !IntSpan64::from("1-3000000000"));
+        let mut s_of = BTreeMap::new();
+        s_of.insert("name".to_string(), set);
+        let bytes = set2bin(&s_of);
+        assert_eq!(bin2set_m::<i64>(&bytes).unwrap(), s_of);
+    }
+
+    #[test]
+    fn test_errors() {
+        let bytes = set2bin(&single(&[("I", "1-100,201-300")]));
+
+        let tests = vec![
+            (b"YAML".to_vec(), BinErrorKind::BadMagic, 0),
+            (
+                bytes[..bytes.len() - 1].to_vec(),
+                BinErrorKind::Truncated,
+                13,
+            ),
+            (
+                [&bytes[..], &[0]].concat(),
+                BinErrorKind::Truncated,
+                bytes.len(),
+            ),
+        ];
+        for (bytes, kind, offset) in tests {
+            let err = bin2set_m::<i32>(&bytes).err().unwrap();
+            assert_eq!(err.kind(), kind);
+            assert_eq!(err.offset(), offset);
+        }
+
+        // beyond i32
+        let s_of = single(&[("I", "1-2147483645")]);
+        let mut bytes = set2bin(&s_of);
+        let last = bytes.len() - 1;
+        bytes[last] += 1;
+        let err = bin2set_m::<i32>(&bytes).err().unwrap();
+        assert_eq!(err.kind(), BinErrorKind::Overflow);
+
+        // zero delta
+        let mut bytes = set2bin(&single(&[("I", "1")]));
+        let last = bytes.len() - 1;
+        bytes[last] = 0;
+        let err = bin2set_m::<i32>(&bytes).err().unwrap();
+        assert_eq!(err.kind(), BinErrorKind::BadOrder);
+    }
+}
@@ -0,0 +1,797 @@
+//! Bitmap sets, for many small islands.
+//!
+//! `IntSpan` stores two edges per island, so sets of scattered positions like SNPs or methylation
+//! calls cost more than the positions themselves. `Bitmap` splits the coordinate range into chunks of
+//! 2^16 integers, as in Roaring bitmaps, and keeps each non-empty chunk as
+//!
+//! * a sorted array of `u16` offsets, up to 4096 elements
+//! * a bitmap of 1024 `u64` words, for denser chunks
+//! * a full marker, for chunks covered entirely
+//!
+//! `IntSet` gathers the operations both representations share. `AutoSet` wraps either one and
+//! picks by island density, so call sets can be mixed with runlists freely.
+//!
+//! ```
+//! use intspan::*;
+//!
+//! let snps: Bitmap = vec![5, 17, 101, 250, 1000].into_iter().collect();
+//! let exons = IntSpan::from("1-20,200-300");
+//!
+//! assert_eq!(snps.intersect_intspan(&exons).to_intspan().to_string(), "5,17,250");
+//!
+//! let snps = AutoSet::from(snps.to_intspan());
+//! assert!(snps.is_bitmap());
+//! let exons = AutoSet::from(exons);
+//! assert!(!exons.is_bitmap());
+//! assert_eq!(snps.intersect(&exons).cardinality(), 3);
+//! ```
+
+use crate::{Coord, GenericIntSpan};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+const WORDS: usize = 1024;
+const ARRAY_MAX: usize = 4096;
+const CHUNK_LEN: u32 = 1 << 16;
+
+/// Islands averaging fewer elements than this are kept as bitmaps by `AutoSet`. An `i32` island
+/// takes 8 bytes of edges, the same as four `u16` offsets.
+const BITMAP_MEAN_ISLAND: i128 = 4;
+
+/// Operations shared by `IntSpan`, `Bitmap` and `AutoSet`
+pub trait IntSet<T: Coord>: Sized {
+    fn contains(&self, n: T) -> bool;
+    fn cardinality(&self) -> T;
+    fn is_empty(&self) -> bool;
+    fn union(&self, other: &Self) -> Self;
+    fn intersect(&self, other: &Self) -> Self;
+    fn to_intspan(&self) -> GenericIntSpan<T>;
+}
+
+impl<T: Coord> IntSet<T> for GenericIntSpan<T> {
+    fn contains(&self, n: T) -> bool {
+        GenericIntSpan::contains(self, n)
+    }
+    fn cardinality(&self) -> T {
+        GenericIntSpan::cardinality(self)
+    }
+    fn is_empty(&self) -> bool {
+        GenericIntSpan::is_empty(self)
+    }
+    fn union(&self, other: &Self) -> Self {
+        GenericIntSpan::union(self, other)
+    }
+    fn intersect(&self, other: &Self) -> Self {
+        GenericIntSpan::intersect(self, other)
+    }
+    fn to_intspan(&self) -> GenericIntSpan<T> {
+        self.clone()
+    }
+}
+
+//----------------------------------------------------------
+// Chunks
+//----------------------------------------------------------
+
+#[derive(Clone, PartialEq, Eq)]
+enum Chunk {
+    // sorted offsets, at most ARRAY_MAX of them
+    Array(Vec<u16>),
+    // bits and their count, between ARRAY_MAX and CHUNK_LEN exclusive
+    Bits(Box<[u64; WORDS]>, u32),
+    Full,
+}
+
+impl Chunk {
+    /// Picks the representation of `words`, `None` if no bit is set
+    fn from_words(words: Box<[u64; WORDS]>) -> Option<Chunk> {
+        let count: u32 = words.iter().map(|w| w.count_ones()).sum();
+        if count == 0 {
+            None
+        } else if count == CHUNK_LEN {
+            Some(Chunk::Full)
+        } else if count as usize <= ARRAY_MAX {
+            Some(Chunk::Array(Chunk::Bits(words, count).iter().collect()))
+        } else {
+            Some(Chunk::Bits(words, count))
+        }
+    }
+
+    fn from_range(lower: u16, upper: u16) -> Chunk {
+        if lower == 0 && upper == u16::MAX {
+            Chunk::Full
+        } else if ((upper - lower) as usize) < ARRAY_MAX {
+            Chunk::Array((lower..=upper).collect())
+        } else {
+            let mut words = Box::new([0u64; WORDS]);
+            fill(&mut words, lower, upper);
+            Chunk::Bits(words, (upper - lower) as u32 + 1)
+        }
+    }
+
+    fn words(&self) -> Box<[u64; WORDS]> {
+        match self {
+            Chunk::Array(offsets) => {
+                let mut words = Box::new([0u64; WORDS]);
+                for &i in offsets {
+                    words[i as usize >> 6] |= 1 << (i & 63);
+                }
+                words
+            }
+            Chunk::Bits(words, _) => words.clone(),
+            Chunk::Full => Box::new([u64::MAX; WORDS]),
+        }
+    }
+
+    fn len(&self) -> u32 {
+        match self {
+            Chunk::Array(offsets) => offsets.len() as u32,
+            Chunk::Bits(_, count) => *count,
+            Chunk::Full => CHUNK_LEN,
+        }
+    }
+
+    fn contains(&self, i: u16) -> bool {
+        match self {
+            Chunk::Array(offsets) => offsets.binary_search(&i).is_ok(),
+            Chunk::Bits(words, _) => words[i as usize >> 6] & (1 << (i & 63)) != 0,
+            Chunk::Full => true,
+        }
+    }
+
+    fn insert(&mut self, i: u16) {
+        match self {
+            Chunk::Array(offsets) => {
+                if let Err(pos) = offsets.binary_search(&i) {
+                    offsets.insert(pos, i);
+                    if offsets.len() > ARRAY_MAX {
+                        *self = Chunk::from_words(self.words()).unwrap();
+                    }
+                }
+            }
+            Chunk::Bits(words, count) => {
+                let bit = 1 << (i & 63);
+                if words[i as usize >> 6] & bit == 0 {
+                    words[i as usize >> 6] |= bit;
+                    *count += 1;
+                    if *count == CHUNK_LEN {
+                        *self = Chunk::Full;
+                    }
+                }
+            }
+            Chunk::Full => {}
+        }
+    }
+
+    fn union(&self, other: &Chunk) -> Chunk {
+        match (self, other) {
+            (Chunk::Full, _) | (_, Chunk::Full) => Chunk::Full,
+            (Chunk::Array(a), Chunk::Array(b)) if a.len() + b.len() <= ARRAY_MAX => {
+                let mut merged = Vec::with_capacity(a.len() + b.len());
+                let (mut i, mut j) = (0, 0);
+                while i < a.len() && j < b.len() {
+                    if a[i] < b[j] {
+                        merged.push(a[i]);
+                        i += 1;
+                    } else if a[i] > b[j] {
+                        merged.push(b[j]);
+                        j += 1;
+                    } else {
+                        merged.push(a[i]);
+                        i += 1;
+                        j += 1;
+                    }
+                }
+                merged.extend_from_slice(&a[i..]);
+                merged.extend_from_slice(&b[j..]);
+                Chunk::Array(merged)
+            }
+            _ => {
+                let mut words = self.words();
+                for (w, o) in words.iter_mut().zip(other.words().iter()) {
+                    *w |= o;
+                }
+                Chunk::from_words(words).unwrap()
+            }
+        }
+    }
+
+    fn intersect(&self, other: &Chunk) -> Option<Chunk> {
+        match (self, other) {
+            (Chunk::Full, c) | (c, Chunk::Full) => Some(c.clone()),
+            (Chunk::Array(a), c) | (c, Chunk::Array(a)) => {
+                let offsets: Vec<u16> = a.iter().copied().filter(|&i| c.contains(i)).collect();
+                if offsets.is_empty() {
+                    None
+                } else {
+                    Some(Chunk::Array(offsets))
+                }
+            }
+            (Chunk::Bits(a, _), Chunk::Bits(b, _)) => {
+                let mut words = a.clone();
+                for (w, o) in words.iter_mut().zip(b.iter()) {
+                    *w &= o;
+                }
+                Chunk::from_words(words)
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            Chunk::Array(offsets) => Box::new(offsets.iter().copied()),
+            Chunk::Bits(words, _) => Box::new(words.iter().enumerate().flat_map(|(k, &w)| {
+                (0..64u16)
+                    .filter(move |b| w & (1 << b) != 0)
+                    .map(move |b| (k as u16) << 6 | b)
+            })),
+            Chunk::Full => Box::new(0..=u16::MAX),
+        }
+    }
+}
+
+//----------------------------------------------------------
+// Bitmap
+//----------------------------------------------------------
+
+/// Chunked bitmap of integers. Empty chunks are never kept, so equal sets are equal structurally
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct GenericBitmap<T> {
+    chunks: BTreeMap<i64, Chunk>,
+    marker: PhantomData<T>,
+}
+
+/// `Bitmap` with `i32` coordinates
+pub type Bitmap = GenericBitmap<i32>;
+
+/// `Bitmap` with `i64` coordinates
+pub type Bitmap64 = GenericBitmap<i64>;
+
+impl<T: Coord> GenericBitmap<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: BTreeMap::new(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn cardinality(&self) -> T {
+        let n: i128 = self.chunks.values().map(|c| c.len() as i128).sum();
+        T::from_i128(n).expect("Cardinality overflow")
+    }
+
+    /// Number of islands, merged across chunks
+    pub fn span_size(&self) -> usize {
+        self.runs().len()
+    }
+
+    pub fn contains(&self, n: T) -> bool {
+        let (key, i) = split(n);
+        self.chunks.get(&key).is_some_and(|c| c.contains(i))
+    }
+
+    pub fn add_n(&mut self, n: T) {
+        let (key, i) = split(n);
+        match self.chunks.get_mut(&key) {
+            Some(chunk) => chunk.insert(i),
+            None => {
+                self.chunks.insert(key, Chunk::Array(vec![i]));
+            }
+        }
+    }
+
+    /// Adds `lower..=upper`, filling chunks in the middle at once
+    ///
+    /// ```
+    /// # use intspan::Bitmap;
+    /// let mut set = Bitmap::new();
+    /// set.add_pair(-10, 200000);
+    /// set.add_n(300000);
+    /// assert_eq!(set.cardinality(), 200012);
+    /// assert_eq!(set.to_intspan().to_string(), "-10-200000,300000");
+    /// ```
+    pub fn add_pair(&mut self, lower: T, upper: T) {
+        if lower > upper {
+            panic!("Bad order: {},{}", lower, upper)
+        }
+        let (key_lower, i_lower) = split(lower);
+        let (key_upper, i_upper) = split(upper);
+
+        for key in key_lower..=key_upper {
+            let lo = if key == key_lower { i_lower } else { 0 };
+            let hi = if key == key_upper { i_upper } else { u16::MAX };
+            let range = Chunk::from_range(lo, hi);
+
+            let chunk = match self.chunks.get(&key) {
+                Some(chunk) => chunk.union(&range),
+                None => range,
+            };
+            self.chunks.insert(key, chunk);
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut new = self.clone();
+        for (key, chunk) in &other.chunks {
+            let merged = match new.chunks.get(key) {
+                Some(mine) => mine.union(chunk),
+                None => chunk.clone(),
+            };
+            new.chunks.insert(*key, merged);
+        }
+
+        new
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut new = Self::new();
+        for (key, chunk) in &self.chunks {
+            if let Some(shared) = other.chunks.get(key).and_then(|c| chunk.intersect(c)) {
+                new.chunks.insert(*key, shared);
+            }
+        }
+
+        new
+    }
+
+    /// Intersects with a runlist without converting either side. Both are walked once in order, and
+    /// spans outside the chunks present here are skipped
+    pub fn intersect_intspan(&self, set: &GenericIntSpan<T>) -> Self {
+        let spans: Vec<(T, T)> = set.spans().collect();
+
+        let mut new = Self::new();
+        let mut first = 0;
+        for (key, chunk) in &self.chunks {
+            let chunk_lower = join(*key, 0);
+            let chunk_upper = join(*key, u16::MAX);
+
+            // spans overlapping this chunk, as offsets within it
+            while first < spans.len() && spans[first].1 < chunk_lower {
+                first += 1;
+            }
+            let ranges: Vec<(u16, u16)> = spans[first..]
+                .iter()
+                .take_while(|(lower, _)| *lower <= chunk_upper)
+                .map(|&(lower, upper)| {
+                    let lo = if lower < chunk_lower {
+                        0
+                    } else {
+                        split(lower).1
+                    };
+                    let hi = if upper > chunk_upper {
+                        u16::MAX
+                    } else {
+                        split(upper).1
+                    };
+                    (lo, hi)
+                })
+                .collect();
+
+            let shared = match chunk {
+                Chunk::Array(offsets) => {
+                    let mut kept = vec![];
+                    let mut r = 0;
+                    for &i in offsets {
+                        while r < ranges.len() && ranges[r].1 < i {
+                            r += 1;
+                        }
+                        if r == ranges.len() {
+                            break;
+                        }
+                        if ranges[r].0 <= i {
+                            kept.push(i);
+                        }
+                    }
+                    if kept.is_empty() {
+                        None
+                    } else {
+                        Some(Chunk::Array(kept))
+                    }
+                }
+                _ => {
+                    let mut mask = Box::new([0u64; WORDS]);
+                    for &(lo, hi) in &ranges {
+                        fill(&mut mask, lo, hi);
+                    }
+                    Chunk::from_words(mask).and_then(|m| chunk.intersect(&m))
+                }
+            };
+
+            if let Some(shared) = shared {
+                new.chunks.insert(*key, shared);
+            }
+        }
+
+        new
+    }
+
+    /// Elements in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.chunks
+            .iter()
+            .flat_map(|(&key, chunk)| chunk.iter().map(move |i| join(key, i)))
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    pub fn to_intspan(&self) -> GenericIntSpan<T> {
+        GenericIntSpan::from_pairs(self.runs())
+    }
+
+    /// Maximal runs of consecutive elements, merged across chunks
+    fn runs(&self) -> Vec<(T, T)> {
+        let mut runs: Vec<(T, T)> = vec![];
+        let mut extend = |lower: T, upper: T| match runs.last_mut() {
+            Some((_, last)) if *last + T::ONE == lower => *last = upper,
+            _ => runs.push((lower, upper)),
+        };
+        for (&key, chunk) in &self.chunks {
+            match chunk {
+                Chunk::Full => extend(join(key, 0), join(key, u16::MAX)),
+                _ => chunk
+                    .iter()
+                    .for_each(|i| extend(join(key, i), join(key, i))),
+            }
+        }
+
+        runs
+    }
+}
+
+/// Sets bits `lower..=upper`, a word at a time
+fn fill(words: &mut [u64; WORDS], lower: u16, upper: u16) {
+    let (lower, upper) = (lower as usize, upper as usize);
+    for (k, word) in words
+        .iter_mut()
+        .enumerate()
+        .take((upper >> 6) + 1)
+        .skip(lower >> 6)
+    {
+        let from = if k == lower >> 6 { lower & 63 } else { 0 };
+        let to = if k == upper >> 6 { upper & 63 } else { 63 };
+        *word |= (u64::MAX >> (63 - to)) & (u64::MAX << from);
+    }
+}
+
+fn split<T: Coord>(n: T) -> (i64, u16) {
+    let n = n.to_i128();
+    ((n >> 16) as i64, (n & 0xFFFF) as u16)
+}
+
+fn join<T: Coord>(key: i64, i: u16) -> T {
+    T::from_i128(((key as i128) << 16) | i as i128).unwrap()
+}
+
+impl<T: Coord> IntSet<T> for GenericBitmap<T> {
+    fn contains(&self, n: T) -> bool {
+        GenericBitmap::contains(self, n)
+    }
+    fn cardinality(&self) -> T {
+        GenericBitmap::cardinality(self)
+    }
+    fn is_empty(&self) -> bool {
+        GenericBitmap::is_empty(self)
+    }
+    fn union(&self, other: &Self) -> Self {
+        GenericBitmap::union(self, other)
+    }
+    fn intersect(&self, other: &Self) -> Self {
+        GenericBitmap::intersect(self, other)
+    }
+    fn to_intspan(&self) -> GenericIntSpan<T> {
+        GenericBitmap::to_intspan(self)
+    }
+}
+
+/// Converted island by island. Each chunk of 2^16 integers touched is stored, even full ones, so
+/// the set must be finite and shouldn't span much of the `i64` range
+///
+/// ```should_panic
+/// # use intspan::{Bitmap64, IntSpan64};
+/// let bitmap = Bitmap64::from(&!IntSpan64::new()); // panics: Bitmap of an infinite set
+/// ```
+impl<T: Coord> From<&GenericIntSpan<T>> for GenericBitmap<T> {
+    fn from(set: &GenericIntSpan<T>) -> Self {
+        assert!(set.is_finite(), "Bitmap of an infinite set");
+
+        let mut new = Self::new();
+        for (lower, upper) in set.spans() {
+            new.add_pair(lower, upper);
+        }
+        new
+    }
+}
+
+impl<T: Coord> FromIterator<T> for GenericBitmap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut new = Self::new();
+        for n in iter {
+            new.add_n(n);
+        }
+        new
+    }
+}
+
+impl<T: Coord> fmt::Debug for GenericBitmap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bitmap({})", self.to_intspan())
+    }
+}
+
+//----------------------------------------------------------
+// AutoSet
+//----------------------------------------------------------
+
+/// Either representation, chosen by island density
+#[derive(Clone)]
+pub enum GenericAutoSet<T> {
+    Spans(GenericIntSpan<T>),
+    Bitmap(GenericBitmap<T>),
+}
+
+/// `AutoSet` with `i32` coordinates
+pub type AutoSet = GenericAutoSet<i32>;
+
+impl<T: Coord> GenericAutoSet<T> {
+    pub fn is_bitmap(&self) -> bool {
+        matches!(self, GenericAutoSet::Bitmap(_))
+    }
+
+    /// Switches to a bitmap when islands average fewer than 4 elements, and back otherwise
+    ///
+    /// ```
+    /// # use intspan::{AutoSet, IntSpan};
+    /// let set = AutoSet::Spans(IntSpan::from("1,3,5,7-8"));
+    /// assert!(set.optimize().is_bitmap());
+    ///
+    /// let set = AutoSet::Spans(IntSpan::from("1-100,201-300"));
+    /// assert!(!set.optimize().is_bitmap());
+    /// ```
+    pub fn optimize(self) -> Self {
+        match self {
+            GenericAutoSet::Spans(set) => {
+                if prefers_bitmap(&set) {
+                    GenericAutoSet::Bitmap(GenericBitmap::from(&set))
+                } else {
+                    GenericAutoSet::Spans(set)
+                }
+            }
+            GenericAutoSet::Bitmap(bitmap) => {
+                let set = bitmap.to_intspan();
+                if prefers_bitmap(&set) {
+                    GenericAutoSet::Bitmap(bitmap)
+                } else {
+                    GenericAutoSet::Spans(set)
+                }
+            }
+        }
+    }
+}
+
+fn prefers_bitmap<T: Coord>(set: &GenericIntSpan<T>) -> bool {
+    !set.is_empty()
+        && set.is_finite()
+        && set.cardinality().to_i128() < set.span_size() as i128 * BITMAP_MEAN_ISLAND
+}
+
+impl<T: Coord> From<GenericIntSpan<T>> for GenericAutoSet<T> {
+    fn from(set: GenericIntSpan<T>) -> Self {
+        GenericAutoSet::Spans(set).optimize()
+    }
+}
+
+impl<T: Coord> From<GenericBitmap<T>> for GenericAutoSet<T> {
+    fn from(bitmap: GenericBitmap<T>) -> Self {
+        GenericAutoSet::Bitmap(bitmap).optimize()
+    }
+}
+
+impl<T: Coord> IntSet<T> for GenericAutoSet<T> {
+    fn contains(&self, n: T) -> bool {
+        match self {
+            GenericAutoSet::Spans(set) => set.contains(n),
+            GenericAutoSet::Bitmap(bitmap) => bitmap.contains(n),
+        }
+    }
+
+    fn cardinality(&self) -> T {
+        match self {
+            GenericAutoSet::Spans(set) => set.cardinality(),
+            GenericAutoSet::Bitmap(bitmap) => bitmap.cardinality(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            GenericAutoSet::Spans(set) => set.is_empty(),
+            GenericAutoSet::Bitmap(bitmap) => bitmap.is_empty(),
+        }
+    }
+
+    /// Mixed operands are merged as runlists
+    fn union(&self, other: &Self) -> Self {
+        let new = match (self, other) {
+            (GenericAutoSet::Spans(a), GenericAutoSet::Spans(b)) => {
+                GenericAutoSet::Spans(a.union(b))
+            }
+            (GenericAutoSet::Bitmap(a), GenericAutoSet::Bitmap(b)) => {
+                GenericAutoSet::Bitmap(a.union(b))
+            }
+            (GenericAutoSet::Spans(set), GenericAutoSet::Bitmap(bitmap))
+            | (GenericAutoSet::Bitmap(bitmap), GenericAutoSet::Spans(set)) => {
+                GenericAutoSet::Spans(set.union(&bitmap.to_intspan()))
+            }
+        };
+
+        new.optimize()
+    }
+
+    /// Mixed operands are intersected with `Bitmap::intersect_intspan()`
+    fn intersect(&self, other: &Self) -> Self {
+        let new = match (self, other) {
+            (GenericAutoSet::Spans(a), GenericAutoSet::Spans(b)) => {
+                GenericAutoSet::Spans(a.intersect(b))
+            }
+            (GenericAutoSet::Bitmap(a), GenericAutoSet::Bitmap(b)) => {
+                GenericAutoSet::Bitmap(a.intersect(b))
+            }
+            (GenericAutoSet::Spans(set), GenericAutoSet::Bitmap(bitmap))
+            | (GenericAutoSet::Bitmap(bitmap), GenericAutoSet::Spans(set)) => {
+                GenericAutoSet::Bitmap(bitmap.intersect_intspan(set))
+            }
+        };
+
+        new.optimize()
+    }
+
+    fn to_intspan(&self) -> GenericIntSpan<T> {
+        match self {
+            GenericAutoSet::Spans(set) => set.clone(),
+            GenericAutoSet::Bitmap(bitmap) => bitmap.to_intspan(),
+        }
+    }
+}
+
+impl<T: Coord> PartialEq for GenericAutoSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GenericAutoSet::Bitmap(a), GenericAutoSet::Bitmap(b)) => a == b,
+            _ => self.to_intspan() == other.to_intspan(),
+        }
+    }
+}
+
+impl<T: Coord> Eq for GenericAutoSet<T> {}
+
+impl<T: Coord> fmt::Debug for GenericAutoSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenericAutoSet::Spans(set) => write!(f, "Spans({:?})", set),
+            GenericAutoSet::Bitmap(bitmap) => write!(f, "Bitmap({:?})", bitmap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntSpan;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random_set(rng: &mut StdRng, n: usize, max_len: i32) -> IntSpan {
+        let mut set = IntSpan::new();
+        for _ in 0..n {
+            let lower = rng.gen_range(-100_000..300_000);
+            set.add_pair(lower, lower + rng.gen_range(0..max_len));
+        }
+        set
+    }
+
+    #[test]
+    fn from_empty() {
+        let set = IntSpan::new();
+        assert!(set.is_finite());
+        assert!(Bitmap::from(&set).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Bitmap of an infinite set")]
+    fn panic_infinite() {
+        let set = IntSpan::from("1-10").complement();
+        println!("{:?}", Bitmap::from(&set));
+    }
+
+    #[test]
+    fn test_chunks() {
+        // array, bits and full chunks
+        for (lower, upper) in &[(1, 100), (1, 10000), (0, 65535), (-70000, 70000)] {
+            let mut bitmap = Bitmap::new();
+            bitmap.add_pair(*lower, *upper);
+            assert_eq!(bitmap.cardinality(), upper - lower + 1);
+            assert_eq!(bitmap.span_size(), 1);
+            assert!(bitmap.contains(*lower));
+            assert!(bitmap.contains(*upper));
+            assert!(!bitmap.contains(lower - 1));
+            assert!(!bitmap.contains(upper + 1));
+        }
+
+        // promotions by single elements
+        let mut bitmap = Bitmap::new();
+        for n in 0..65536 {
+            bitmap.add_n(n);
+        }
+        assert!(matches!(bitmap.chunks.values().next(), Some(Chunk::Full)));
+
+        // demotion keeps equality structural
+        let mut a = Bitmap::new();
+        a.add_pair(1, 10000);
+        let mut b = Bitmap::new();
+        b.add_pair(9001, 20000);
+        let mut expected = Bitmap::new();
+        expected.add_pair(9001, 10000);
+        assert_eq!(a.intersect(&b), expected);
+
+        // full chunks masked by runlists
+        let mut full = Bitmap::new();
+        full.add_pair(0, 131071);
+        let masked = full.intersect_intspan(&IntSpan::from("10-20000,70000"));
+        assert_eq!(masked.cardinality(), 19992);
+        assert_eq!(masked.to_intspan().to_string(), "10-20000,70000");
+    }
+
+    #[test]
+    fn test_same_as_intspan() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for &(n, max_len) in &[(50, 3), (3000, 3), (500, 5000), (20, 200_000)] {
+            let a = random_set(&mut rng, n, max_len);
+            let b = random_set(&mut rng, n, max_len);
+            let bm_a = Bitmap::from(&a);
+            let bm_b = Bitmap::from(&b);
+
+            assert_eq!(bm_a.to_intspan(), a);
+            assert_eq!(bm_a.cardinality(), a.cardinality());
+            assert_eq!(bm_a.span_size(), a.span_size());
+            assert_eq!(bm_a.to_vec(), a.to_vec());
+            assert_eq!(bm_a.union(&bm_b).to_intspan(), a.union(&b));
+            assert_eq!(bm_a.intersect(&bm_b).to_intspan(), a.intersect(&b));
+            assert_eq!(bm_a.intersect_intspan(&b).to_intspan(), a.intersect(&b));
+
+            for _ in 0..100 {
+                let k = rng.gen_range(-100_000..300_000);
+                assert_eq!(bm_a.contains(k), a.contains(k));
+            }
+
+            let auto_a = AutoSet::from(a.clone());
+            let auto_b = AutoSet::Bitmap(bm_b.clone());
+            assert_eq!(auto_a.union(&auto_b).to_intspan(), a.union(&b));
+            assert_eq!(auto_a.intersect(&auto_b).to_intspan(), a.intersect(&b));
+            assert_eq!(auto_b.intersect(&auto_a), AutoSet::Spans(a.intersect(&b)));
+        }
+    }
+
+    #[test]
+    fn test_auto_set() {
+        assert!(!AutoSet::from(IntSpan::new()).is_bitmap());
+        assert!(!AutoSet::from(!IntSpan::new()).is_bitmap());
+        assert!(AutoSet::from(IntSpan::from("1,3,5")).is_bitmap());
+
+        // dense results switch back to runlists
+        let snps = AutoSet::from(IntSpan::from("1,3,5"));
+        let filled = snps.union(&AutoSet::from(IntSpan::from("2-100")));
+        assert!(!filled.is_bitmap());
+        assert_eq!(filled.cardinality(), 100);
+
+        let bitmap: Bitmap64 = vec![3_000_000_000i64, 5, -5].into_iter().collect();
+        assert_eq!(bitmap.to_intspan().to_string(), "-5,5,3000000000");
+        assert_eq!(format!("{:?}", bitmap), "Bitmap(-5,5,3000000000)");
+    }
+}
@@ -1,6 +1,5 @@
 use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use serde_yaml::Value;
 use std::collections::BTreeMap;
 
 // Create clap subcommand arguments
@@ -29,12 +28,12 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap());
-    let s_of = yaml2set_m(&yaml);
+    let s_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile").unwrap())?;
     let chrs = chrs_in_sets(&s_of);
 
     //----------------------------
@@ -42,21 +41,18 @@ pub fn execute(args: &ArgMatches) {
     //----------------------------
     let mut res: BTreeMap<String, IntSpan> = BTreeMap::new();
     for chr in &chrs {
-        res.insert(chr.to_string(), IntSpan::new());
-    }
-
-    for name in s_of.keys() {
-        let set = s_of.get(name.as_str()).unwrap();
-        for chr in set.keys() {
-            let cur_runlist = set.get(chr).unwrap().to_string();
-            res.entry(chr.to_string())
-                .and_modify(|e| e.add_runlist(cur_runlist));
-        }
+        let pairs = s_of
+            .values()
+            .filter_map(|set| set.get(chr))
+            .flat_map(|intspan| intspan.spans());
+        res.insert(chr.to_string(), IntSpan::from_pairs(pairs));
     }
 
     //----------------------------
     // Output
     //----------------------------
     let out_yaml = set2yaml(&res);
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
@@ -1,6 +1,5 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use serde_yaml::Value;
 use std::collections::BTreeMap;
 
 // Create clap subcommand arguments
@@ -40,16 +39,15 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile1").unwrap());
-    let is_multi: bool = yaml.values().next().unwrap().is_mapping();
-    let mut s1_of = yaml2set_m(&yaml);
+    let mut s1_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile1").unwrap())?;
+    let is_multi: bool = !s1_of.contains_key("__single");
 
-    let yaml_s: BTreeMap<String, Value> = read_yaml(args.value_of("infile2").unwrap());
-    let mut s2 = yaml2set(&yaml_s);
+    let mut s2: BTreeMap<String, IntSpan> = read_set(args.value_of("infile2").unwrap())?;
 
     let op = args.value_of("op").unwrap();
 
@@ -73,7 +71,7 @@ pub fn execute(args: &ArgMatches) {
                 "diff" => s1.get(chr).unwrap().diff(s2.get(chr).unwrap()),
                 "union" => s1.get(chr).unwrap().union(s2.get(chr).unwrap()),
                 "xor" => s1.get(chr).unwrap().xor(s2.get(chr).unwrap()),
-                _ => panic!("Invalid IntSpan Op"),
+                _ => return Err(Error::args(format!("Invalid IntSpan Op: {}", op))),
             };
             //            println!("Op {}: {}", op, op_intspan.to_string());
             res.insert(chr.into(), intspan_op);
@@ -87,7 +85,9 @@ pub fn execute(args: &ArgMatches) {
     let out_yaml = if is_multi {
         set2yaml_m(&res_of)
     } else {
-        set2yaml(&res_of.get("__single").unwrap())
+        set2yaml(res_of.get("__single").unwrap())
     };
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
@@ -1,10 +1,18 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
+use std::collections::BTreeMap;
 
 // Create clap subcommand arguments
 pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("convert")
         .about("Convert runlist file to ranges file")
+        .after_help(
+            "\
+With --to bin, a single runlist file is written as binary runlists, which every
+subcommand reads in place of YAML. Binary files are detected by their leading
+bytes `ISPB`. \
+            ",
+        )
         .arg(
             Arg::with_name("infiles")
                 .help("Sets the input file to use")
@@ -12,6 +20,22 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .min_values(1)
                 .index(1),
         )
+        .arg(
+            Arg::with_name("to")
+                .long("to")
+                .takes_value(true)
+                .default_value("ranges")
+                .empty_values(false)
+                .help("formats: ranges or bin"),
+        )
+        .arg(
+            Arg::with_name("order")
+                .long("order")
+                .takes_value(true)
+                .default_value("lexical")
+                .empty_values(false)
+                .help("Order of chromosomes: lexical, natural, roman, or a chr.sizes file"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -24,38 +48,50 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let mut writer = writer(args.value_of("outfile").unwrap());
+    let infiles: Vec<&str> = args.values_of("infiles").unwrap().collect();
+    let order = ChrOrder::parse(args.value_of("order").unwrap())?;
+
+    match args.value_of("to").unwrap() {
+        "ranges" => {}
+        "bin" => {
+            if infiles.len() != 1 {
+                return Err(Error::args("--to bin takes a single input file"));
+            }
+            let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = read_set_m(infiles[0])?;
+            write_bin(args.value_of("outfile").unwrap(), &set_of)?;
+            return Ok(());
+        }
+        to => return Err(Error::args(format!("Invalid format: {}", to))),
+    }
+
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
 
     //----------------------------
     // Operating
     //----------------------------
-    for infile in args.values_of("infiles").unwrap() {
-        let yaml = read_yaml(infile);
-        let set_of = yaml2set_m(&yaml);
+    for infile in infiles {
+        let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = read_set_m(infile)?;
 
         for set in set_of.values() {
-            for chr in set.keys() {
+            for chr in order.sorted(set.keys()) {
                 let intspan = set.get(chr).unwrap();
-                let span_size = intspan.span_size();
-                let ranges = intspan.ranges();
-                for i in 0..span_size {
-                    let lower = *ranges.get(i * 2).unwrap();
-                    let upper = *ranges.get(i * 2 + 1).unwrap();
-
+                for (lower, upper) in intspan.spans() {
                     //----------------------------
                     // Output
                     //----------------------------
                     if lower == upper {
-                        writer.write_all(format!("{}:{}\n", chr, lower).as_ref());
+                        writer.write_all(format!("{}:{}\n", chr, lower).as_ref())?;
                     } else {
-                        writer.write_all(format!("{}:{}-{}\n", chr, lower, upper).as_ref());
+                        writer.write_all(format!("{}:{}-{}\n", chr, lower, upper).as_ref())?;
                     }
                 }
             }
         }
     }
+
+    Ok(())
 }
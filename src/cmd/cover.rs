@@ -1,4 +1,4 @@
-use clap::*;
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
 use intspan::*;
 use std::collections::BTreeMap;
 use std::io::BufRead;
@@ -34,6 +34,19 @@ Like command `combine`, but <infiles> are chromosome ranges
                 .default_value("1")
                 .empty_values(false),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
+        .arg(
+            Arg::with_name("order")
+                .long("order")
+                .takes_value(true)
+                .default_value("lexical")
+                .empty_values(false)
+                .help("Order of chromosomes: lexical, natural, roman, or a chr.sizes file"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -46,43 +59,56 @@ Like command `combine`, but <infiles> are chromosome ranges
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let coverage: i32 = value_t!(args.value_of("coverage"), i32).unwrap_or_else(|e| {
-        eprintln!("Need a integer for --coverage\n{}", e);
-        std::process::exit(1)
-    });
+    let coverage: i32 = value_t!(args.value_of("coverage"), i32)
+        .map_err(|_| Error::args("Need a integer for --coverage"))?;
+    let strict = args.is_present("strict");
+    let order = ChrOrder::parse(args.value_of("order").unwrap())?;
 
-    // seq_name => tier_of => IntSpan
-    let mut res: BTreeMap<String, Coverage> = BTreeMap::new();
+    // seq_name => (start, end)s
+    let mut pairs_of: BTreeMap<String, Vec<(i32, i32)>> = BTreeMap::new();
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
-            let range = Range::from_str(line);
-            if !range.is_valid() {
-                continue;
-            }
-            let chr = range.chr();
-            if !res.contains_key(chr) {
-                let tiers = Coverage::new(coverage);
-                res.insert(chr.clone(), tiers);
-            }
-
-            res.entry(chr.to_string())
-                .and_modify(|e| e.bump(range.start().clone(), range.end().clone()));
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
+            let range = match parse_range(&line, infile, i + 1, strict)? {
+                Some(range) => range,
+                None => continue,
+            };
+            pairs_of
+                .entry(range.chr().to_string())
+                .or_default()
+                .push((*range.start(), *range.end()));
         }
     }
 
     //----------------------------
-    // Output
+    // Operating
     //----------------------------
     let mut set: BTreeMap<String, IntSpan> = BTreeMap::new();
-    for chr in res.keys() {
-        set.insert(chr.to_string(), res.get(chr).unwrap().max_tier());
+    for (chr, pairs) in pairs_of {
+        let intspan = if coverage == 1 {
+            // the first tier is just the union
+            IntSpan::from_pairs(pairs)
+        } else {
+            let mut tiers = Coverage::new(coverage);
+            for (start, end) in pairs {
+                tiers.bump(start, end);
+            }
+            tiers.max_tier()
+        };
+        set.insert(chr, intspan);
     }
+
+    //----------------------------
+    // Output
+    //----------------------------
     let out_yaml = set2yaml(&set);
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml_in(args.value_of("outfile").unwrap(), &out_yaml, &order)?;
+
+    Ok(())
 }
@@ -0,0 +1,83 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use std::collections::BTreeMap;
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("eval")
+        .about("Evaluate an expression of set and span operations")
+        .after_help(
+            "\
+Operators, loosest first
+    |   union
+    ^   xor
+    &   intersect
+    -   diff
+    !   complement, prefix
+
+Span ops are called as methods: pad(n), trim(n), inset(n), fill(n), excise(n),
+holes() and cover(). Parentheses group.
+
+Every name in a multi-name file binds a set, as does the file stem of a
+single-name file, and must be unique. Names other than letters, digits and `_`
+go in double quotes.
+
+    intspan eval 'exons & !repeat | (genes.pad(500) - intergenic)' \\
+        exons.yml repeat.yml genes.yml intergenic.yml
+    intspan eval '\"AT1G01010.1\" | \"AT1G01020.1\"' Atha.yml \
+            ",
+        )
+        .arg(
+            Arg::with_name("expr")
+                .help("The expression")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("infiles")
+                .help("Sets the input files to use")
+                .required(true)
+                .min_values(1)
+                .index(2),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let src = args.value_of("expr").unwrap();
+    let expr = Expr::parse(src).map_err(|e| Error::expr(src, e))?;
+
+    let mut set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = BTreeMap::new();
+    for infile in args.values_of("infiles").unwrap() {
+        for (name, set) in read_set_labelled(infile)? {
+            if set_of.contains_key(&name) {
+                return Err(Error::args(format!("Duplicate set name: {}", name)));
+            }
+            set_of.insert(name, set);
+        }
+    }
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let res = expr.eval_map(&set_of).map_err(|e| Error::expr(src, e))?;
+
+    //----------------------------
+    // Output
+    //----------------------------
+    write_yaml(args.value_of("outfile").unwrap(), &set2yaml(&res))?;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("flank")
+        .about("Flanking regions or extensions of ranges, strand-aware")
+        .after_help(
+            "\
+List of operations
+    flank:  the upstream and downstream regions, one range per line
+    slop:   ranges extended by both
+
+Upstream and downstream follow the strand of each range, so on `-` upstream is
+towards higher coordinates. Ranges without a strand are treated as `+`.
+Positions stop at 1, and at chrLength with --sizes. Empty flanks are omitted. \
+            ",
+        )
+        .arg(
+            Arg::with_name("ranges")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("op")
+                .long("op")
+                .takes_value(true)
+                .default_value("flank")
+                .empty_values(false)
+                .help("operations: flank or slop"),
+        )
+        .arg(
+            Arg::with_name("up")
+                .long("up")
+                .takes_value(true)
+                .default_value("0")
+                .empty_values(false)
+                .help("Integers upstream"),
+        )
+        .arg(
+            Arg::with_name("down")
+                .long("down")
+                .takes_value(true)
+                .default_value("0")
+                .empty_values(false)
+                .help("Integers downstream"),
+        )
+        .arg(
+            Arg::with_name("sizes")
+                .long("sizes")
+                .short("s")
+                .takes_value(true)
+                .empty_values(false)
+                .help("chr.sizes bounding the results"),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let infile = args.value_of("ranges").unwrap();
+    let reader = reader(infile)?;
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
+    let strict = args.is_present("strict");
+
+    let op = args.value_of("op").unwrap();
+    let up: i32 =
+        value_t!(args.value_of("up"), i32).map_err(|_| Error::args("Need a integer for --up"))?;
+    let down: i32 = value_t!(args.value_of("down"), i32)
+        .map_err(|_| Error::args("Need a integer for --down"))?;
+    if op != "flank" && op != "slop" {
+        return Err(Error::args(format!("Invalid Range Op: {}", op)));
+    }
+    if up < 0 || down < 0 {
+        return Err(Error::args("--up and --down should not be negative"));
+    }
+    let sizes: Option<BTreeMap<String, i32>> =
+        args.value_of("sizes").map(read_sizes).transpose()?;
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| Error::io(infile, e))?;
+        let range = match parse_range(&line, infile, i + 1, strict)? {
+            Some(range) => range,
+            None => continue,
+        };
+
+        let results: Vec<Range> = match op {
+            "flank" => {
+                let (upstream, downstream) = range.flank(up, down);
+                upstream.into_iter().chain(downstream).collect()
+            }
+            "slop" => vec![range.slop(up, down)],
+            _ => unreachable!(),
+        };
+
+        //----------------------------
+        // Output
+        //----------------------------
+        for result in results {
+            let result = match sizes.as_ref().and_then(|s| s.get(result.chr())) {
+                Some(size) => result.clip(*size),
+                None => Some(result),
+            };
+            if let Some(result) = result {
+                writer.write_all(format!("{}\n", result).as_ref())?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -25,11 +25,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let sizes = read_sizes(args.value_of("infile").unwrap());
+    let sizes = read_sizes(args.value_of("infile").unwrap())?;
 
     //----------------------------
     // Operating
@@ -44,5 +44,7 @@ pub fn execute(args: &ArgMatches) {
     //----------------------------
     // Output
     //----------------------------
-    write_yaml(args.value_of("outfile").unwrap(), &yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &yaml)?;
+
+    Ok(())
 }
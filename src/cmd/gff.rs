@@ -32,11 +32,12 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let mut res: BTreeMap<String, IntSpan> = BTreeMap::new();
+    // chr => (start, end)s
+    let mut pairs_of: BTreeMap<String, Vec<(i32, i32)>> = BTreeMap::new();
     let tag = if args.is_present("tag") {
         args.value_of("tag").unwrap()
     } else {
@@ -44,8 +45,9 @@ pub fn execute(args: &ArgMatches) {
     };
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
             if line.starts_with('#') {
                 continue;
             }
@@ -61,21 +63,34 @@ pub fn execute(args: &ArgMatches) {
             }
 
             let chr = fields[0];
-            let start = fields[3].parse::<i32>().unwrap();
-            let end = fields[4].parse::<i32>().unwrap();
+            let parse = |field: &str| {
+                field.parse::<i32>().map_err(|e| {
+                    Error::parse(infile, i + 1, format!("Invalid position {}: {}", field, e))
+                })
+            };
+            let start = parse(fields[3])?;
+            let end = parse(fields[4])?;
 
-            if !res.contains_key(chr) {
-                let intspan = IntSpan::new();
-                res.insert(chr.to_string(), intspan);
-            }
-            res.entry(chr.to_string())
-                .and_modify(|e| e.add_pair(start, end));
+            pairs_of
+                .entry(chr.to_string())
+                .or_default()
+                .push((start, end));
         }
     }
 
+    //----------------------------
+    // Operating
+    //----------------------------
+    let res: BTreeMap<String, IntSpan> = pairs_of
+        .into_iter()
+        .map(|(chr, pairs)| (chr, IntSpan::from_pairs(pairs)))
+        .collect();
+
     //----------------------------
     // Output
     //----------------------------
     let out_yaml = set2yaml(&res);
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
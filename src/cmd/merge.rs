@@ -28,26 +28,28 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
     let mut out_yaml: BTreeMap<String, Value> = BTreeMap::new();
 
     for infile in args.values_of("infiles").unwrap() {
-        let yaml = read_yaml(infile);
+        let yaml = read_yaml(infile)?;
         out_yaml.insert(
             Path::new(infile)
                 .file_stem()
                 .and_then(OsStr::to_str)
                 .unwrap()
                 .to_string(),
-            serde_yaml::to_value(yaml).unwrap(),
+            serde_yaml::to_value(yaml)?,
         );
     }
 
     //----------------------------
     // Output
     //----------------------------
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
@@ -4,12 +4,18 @@ pub mod combine;
 pub mod compare;
 pub mod convert;
 pub mod cover;
+pub mod eval;
+pub mod flank;
 pub mod genome;
 pub mod gff;
 pub mod merge;
+pub mod nway;
 pub mod range;
+pub mod shuffle;
+pub mod similarity;
 pub mod some;
 pub mod span;
 pub mod split;
 pub mod stat;
 pub mod statop;
+pub mod window;
@@ -0,0 +1,168 @@
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("nway")
+        .about("N-way operations over many YAML files")
+        .after_help(
+            "\
+List of operations
+    intersect:  in all of the sets
+    union:      in any of the sets
+    at-least:   in k or more of the sets, see -k
+    exclusive:  for each set, in no other set
+    upset:      a membership table, the size of each combination per chromosome
+
+Every name in a multi-name file is a set of its own, labelled by the name. A
+single-name file is labelled by its file stem. Labels must be unique.
+
+intersect, union and at-least write a single-name YAML, exclusive writes one
+name per set. upset writes tab-separated columns: chr, one 0/1 column per set
+and size. \
+            ",
+        )
+        .arg(
+            Arg::with_name("infiles")
+                .help("Sets the input files to use")
+                .required(true)
+                .min_values(1)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("op")
+                .long("op")
+                .takes_value(true)
+                .default_value("intersect")
+                .empty_values(false)
+                .help("operations: intersect, union, at-least, exclusive or upset"),
+        )
+        .arg(
+            Arg::with_name("k")
+                .short("k")
+                .takes_value(true)
+                .default_value("1")
+                .empty_values(false)
+                .help("Minimal number of sets for at-least"),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let mut labels: Vec<String> = vec![];
+    let mut sets: Vec<BTreeMap<String, IntSpan>> = vec![];
+    for infile in args.values_of("infiles").unwrap() {
+        for (label, set) in read_set_labelled(infile)? {
+            if labels.contains(&label) {
+                return Err(Error::args(format!("Duplicate set label: {}", label)));
+            }
+            labels.push(label);
+            sets.push(set);
+        }
+    }
+
+    let op = args.value_of("op").unwrap();
+    let k: usize =
+        value_t!(args.value_of("k"), usize).map_err(|_| Error::args("Need a integer for -k"))?;
+    if op == "at-least" && (k == 0 || k > sets.len()) {
+        return Err(Error::args(format!(
+            "-k should be between 1 and {}, the number of sets",
+            sets.len()
+        )));
+    }
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let mut chrs: BTreeSet<String> = BTreeSet::new();
+    for set in &sets {
+        for chr in set.keys() {
+            chrs.insert(chr.to_string());
+        }
+    }
+
+    let empty = IntSpan::new();
+    let mut res_of: BTreeMap<String, BTreeMap<String, IntSpan>> = BTreeMap::new();
+    let mut lines: Vec<String> = vec![];
+    if op == "upset" {
+        lines.push(format!("chr\t{}\tsize", labels.join("\t")));
+    }
+    for chr in &chrs {
+        let refs: Vec<&IntSpan> = sets
+            .iter()
+            .map(|set| set.get(chr).unwrap_or(&empty))
+            .collect();
+
+        match op {
+            "intersect" | "union" | "at-least" => {
+                let intspan = match op {
+                    "intersect" => IntSpan::intersect_all(&refs),
+                    "union" => IntSpan::union_all(&refs),
+                    _ => IntSpan::at_least(&refs, k),
+                };
+                res_of
+                    .entry("__single".to_string())
+                    .or_default()
+                    .insert(chr.to_string(), intspan);
+            }
+            "exclusive" => {
+                for (label, intspan) in labels.iter().zip(IntSpan::exclusive(&refs)) {
+                    res_of
+                        .entry(label.to_string())
+                        .or_default()
+                        .insert(chr.to_string(), intspan);
+                }
+            }
+            "upset" => {
+                let mut size_of: BTreeMap<Vec<bool>, i32> = BTreeMap::new();
+                for (lower, upper, members) in IntSpan::membership(&refs) {
+                    *size_of.entry(members).or_insert(0) += upper - lower + 1;
+                }
+                // combinations with more sets first
+                let mut combos: Vec<(Vec<bool>, i32)> = size_of.into_iter().collect();
+                combos.sort_by_key(|(members, _)| {
+                    std::cmp::Reverse(members.iter().filter(|&&m| m).count())
+                });
+                for (members, size) in combos {
+                    let flags: Vec<&str> =
+                        members.iter().map(|&m| if m { "1" } else { "0" }).collect();
+                    lines.push(format!("{}\t{}\t{}", chr, flags.join("\t"), size));
+                }
+            }
+            _ => return Err(Error::args(format!("Invalid N-way Op: {}", op))),
+        }
+    }
+
+    //----------------------------
+    // Output
+    //----------------------------
+    if op == "upset" {
+        let mut writer = writer(args.value_of("outfile").unwrap())?;
+        for line in lines {
+            writer.write_all(format!("{}\n", line).as_ref())?;
+        }
+        return Ok(());
+    }
+
+    let out_yaml = if op == "exclusive" {
+        set2yaml_m(&res_of)
+    } else {
+        set2yaml(&res_of.remove("__single").unwrap_or_default())
+    };
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
+}
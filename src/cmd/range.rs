@@ -1,5 +1,6 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
+use std::collections::BTreeMap;
 use std::io::BufRead;
 
 // Create clap subcommand arguments
@@ -26,6 +27,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .empty_values(false)
                 .help("operations: overlap, non-overlap or superset"),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -38,29 +44,31 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml = read_yaml(args.value_of("runlist").unwrap());
-    let set = yaml2set(&yaml);
+    let set: BTreeMap<String, IntSpan> = read_set(args.value_of("runlist").unwrap())?;
 
-    let reader = reader(args.value_of("ranges").unwrap());
-    let mut writer = writer(args.value_of("outfile").unwrap());
+    let infile = args.value_of("ranges").unwrap();
+    let reader = reader(infile)?;
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
 
     let op = args.value_of("op").unwrap();
+    let strict = args.is_present("strict");
 
     //----------------------------
     // Operating
     //----------------------------
-    for line in reader.lines().filter_map(|r| r.ok()) {
-        let range = Range::from_str(line.clone());
-        if !range.is_valid() {
-            continue;
-        }
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| Error::io(infile, e))?;
+        let range = match parse_range(&line, infile, i + 1, strict)? {
+            Some(range) => range,
+            None => continue,
+        };
         let chr = range.chr();
         let mut intspan = IntSpan::new();
-        intspan.add_pair(range.start().clone(), range.end().clone());
+        intspan.add_pair(*range.start(), *range.end());
 
         //----------------------------
         // Output
@@ -68,24 +76,26 @@ pub fn execute(args: &ArgMatches) {
         match op {
             "overlap" => {
                 if set.contains_key(chr) && !set.get(chr).unwrap().intersect(&intspan).is_empty() {
-                    writer.write_all((line + "\n").as_ref());
+                    writer.write_all((line + "\n").as_ref())?;
                 }
             }
             "non-overlap" => {
                 if set.contains_key(chr) {
                     if set.get(chr).unwrap().intersect(&intspan).is_empty() {
-                        writer.write_all((line + "\n").as_ref());
+                        writer.write_all((line + "\n").as_ref())?;
                     }
                 } else {
-                    writer.write_all((line + "\n").as_ref());
+                    writer.write_all((line + "\n").as_ref())?;
                 }
             }
             "superset" => {
                 if set.contains_key(chr) && set.get(chr).unwrap().superset(&intspan) {
-                    writer.write_all((line + "\n").as_ref());
+                    writer.write_all((line + "\n").as_ref())?;
                 }
             }
-            _ => panic!("Invalid Range Op"),
+            _ => return Err(Error::args(format!("Invalid Range Op: {}", op))),
         };
     }
+
+    Ok(())
 }
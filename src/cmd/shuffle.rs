@@ -0,0 +1,132 @@
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("shuffle")
+        .about("Randomly relocate ranges within chromosomes")
+        .after_help(
+            "\
+Each range keeps its name, chromosome, strand, length and attributes, and is
+placed uniformly among all positions where it fits entirely inside the
+chromosome. With --mask, it must also fit entirely inside a single island of
+the runlist.
+
+Ranges that fit nowhere are reported to stderr and dropped.
+Use --seed for reproducible output. \
+            ",
+        )
+        .arg(
+            Arg::with_name("chr.sizes")
+                .help("Chromosome sizes, the bounds of the placements")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("ranges")
+                .help("Ranges to relocate, one per line. [stdin] for screen")
+                .required(true)
+                .index(2),
+        )
+        .arg(
+            Arg::with_name("mask")
+                .long("mask")
+                .takes_value(true)
+                .empty_values(false)
+                .help("Single-name runlist file restricting the placements"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .empty_values(false)
+                .help("Seed of the random number generator"),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let sizes = read_sizes(args.value_of("chr.sizes").unwrap())?;
+
+    let mask: Option<BTreeMap<String, IntSpan>> =
+        args.value_of("mask").map(read_set).transpose()?;
+
+    let mut rng = if args.is_present("seed") {
+        let seed: u64 = value_t!(args.value_of("seed"), u64)
+            .map_err(|_| Error::args("Need a integer for --seed"))?;
+        StdRng::seed_from_u64(seed)
+    } else {
+        StdRng::from_entropy()
+    };
+
+    let infile = args.value_of("ranges").unwrap();
+    let reader = reader(infile)?;
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
+    let strict = args.is_present("strict");
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    // chr => allowed positions
+    let mut domain_of: BTreeMap<String, IntSpan> = BTreeMap::new();
+    for (chr, size) in &sizes {
+        let mut domain = IntSpan::new();
+        domain.add_pair(1, *size);
+        if let Some(mask) = &mask {
+            domain = match mask.get(chr) {
+                Some(set) => domain.intersect(set),
+                None => IntSpan::new(),
+            };
+        }
+        domain_of.insert(chr.to_string(), domain);
+    }
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| Error::io(infile, e))?;
+        let range = match parse_range(&line, infile, i + 1, strict)? {
+            Some(range) => range,
+            None => continue,
+        };
+
+        let len = *range.end() - *range.start() + 1;
+        let placed = domain_of
+            .get(range.chr())
+            .and_then(|domain| domain.sample_interval(len, &mut rng));
+
+        //----------------------------
+        // Output
+        //----------------------------
+        match placed {
+            Some((start, end)) => {
+                let mut shuffled = range.clone();
+                *shuffled.start_mut() = start;
+                *shuffled.end_mut() = end;
+                writer.write_all(format!("{}\n", shuffled).as_ref())?;
+            }
+            None => eprintln!("No room for {}", line),
+        }
+    }
+
+    Ok(())
+}
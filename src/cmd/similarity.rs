@@ -0,0 +1,105 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("similarity")
+        .about("All-vs-all similarity of names in a runlist file")
+        .after_help(
+            "\
+List of metrics
+    jaccard:    |A ∩ B| / |A ∪ B|
+    dice:       2|A ∩ B| / (|A| + |B|)
+    overlap:    |A ∩ B| / min(|A|, |B|)
+    distance:   |A ∪ B| - |A ∩ B|, in bp
+
+Sizes are summed over chromosomes. The output is a tab-separated matrix with a
+header line of names. A single-name file is named by its file stem. \
+            ",
+        )
+        .arg(
+            Arg::with_name("infile")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("metric")
+                .long("metric")
+                .takes_value(true)
+                .default_value("jaccard")
+                .empty_values(false)
+                .help("metrics: jaccard, dice, overlap or distance"),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let infile = args.value_of("infile").unwrap();
+    let yaml = read_yaml(infile)?;
+    let is_multi = yaml.values().next().is_some_and(|v| v.is_mapping());
+
+    // a single-name file holds one unnamed set, shown under the file stem
+    let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> = if is_multi {
+        yaml2set_m(&yaml)
+    } else {
+        let stem = Path::new(infile)
+            .file_stem()
+            .map_or_else(|| infile.to_string(), |s| s.to_string_lossy().to_string());
+        yaml2set(&yaml).map(|set| BTreeMap::from([(stem, set)]))
+    }
+    .map_err(|e| e.in_file(infile))?;
+    let metric = args.value_of("metric").unwrap();
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    let names: Vec<&String> = set_of.keys().collect();
+    let mut lines: Vec<String> = vec![];
+    lines.push(
+        std::iter::once("name")
+            .chain(names.iter().map(|s| s.as_str()))
+            .collect::<Vec<&str>>()
+            .join("\t"),
+    );
+
+    for a in &names {
+        let mut fields: Vec<String> = vec![a.to_string()];
+        for b in &names {
+            let sim = Similarity::of_map(&set_of[*a], &set_of[*b]);
+            let field = match metric {
+                "jaccard" => format!("{:.4}", sim.jaccard()),
+                "dice" => format!("{:.4}", sim.dice()),
+                "overlap" => format!("{:.4}", sim.overlap_coefficient()),
+                "distance" => format!("{}", sim.bp_distance()),
+                _ => return Err(Error::args(format!("Invalid metric: {}", metric))),
+            };
+            fields.push(field);
+        }
+        lines.push(fields.join("\t"));
+    }
+
+    //----------------------------
+    // Output
+    //----------------------------
+    for line in lines {
+        writer.write_all(format!("{}\n", line).as_ref())?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
 use serde_yaml::Value;
 use std::collections::{BTreeMap, BTreeSet};
@@ -31,14 +31,14 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap());
+    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap())?;
 
     let mut names: BTreeSet<String> = BTreeSet::new();
-    for line in read_lines(args.value_of("list").unwrap()) {
+    for line in read_lines(args.value_of("list").unwrap())? {
         names.insert(line);
     }
 
@@ -55,5 +55,7 @@ pub fn execute(args: &ArgMatches) {
     //----------------------------
     // Output
     //----------------------------
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
@@ -1,6 +1,5 @@
-use clap::*;
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use serde_yaml::Value;
 use std::collections::BTreeMap;
 
 // Create clap subcommand arguments
@@ -15,7 +14,14 @@ List of operations
     trim:   remove N integers from each end of each span of runlist
     pad:    add N integers from each end of each span of runlist
     excise: remove all spans smaller than N
-    fill:   fill in all holes smaller than or equals to N \
+    fill:   fill in all holes smaller than or equals to N
+    slop:   alias of pad
+    flank:  integers added by pad, excluding the runlist itself
+    keep-largest: keep the N largest spans of each chromosome
+    size-range:   keep spans of --min to --max integers
+
+--left and --right override N on the lower and upper ends for trim, pad, slop
+and flank. With --sizes, results are bounded by chromosomes, 1 to chrLength. \
             ",
         )
         .arg(
@@ -30,7 +36,10 @@ List of operations
                 .takes_value(true)
                 .default_value("cover")
                 .empty_values(false)
-                .help("operations: cover, holes, trim, pad, excise or fill"),
+                .help(
+                    "operations: cover, holes, trim, pad, excise, fill, slop, flank, \
+                     keep-largest or size-range",
+                ),
         )
         .arg(
             Arg::with_name("number")
@@ -40,6 +49,43 @@ List of operations
                 .default_value("0")
                 .empty_values(false),
         )
+        .arg(
+            Arg::with_name("left")
+                .long("left")
+                .takes_value(true)
+                .empty_values(false)
+                .help("Integers on the lower end. Defaults to --number"),
+        )
+        .arg(
+            Arg::with_name("right")
+                .long("right")
+                .takes_value(true)
+                .empty_values(false)
+                .help("Integers on the upper end. Defaults to --number"),
+        )
+        .arg(
+            Arg::with_name("min")
+                .long("min")
+                .takes_value(true)
+                .default_value("1")
+                .empty_values(false)
+                .help("Minimal span length for size-range"),
+        )
+        .arg(
+            Arg::with_name("max")
+                .long("max")
+                .takes_value(true)
+                .empty_values(false)
+                .help("Maximal span length for size-range. Defaults to no limit"),
+        )
+        .arg(
+            Arg::with_name("sizes")
+                .long("sizes")
+                .short("s")
+                .takes_value(true)
+                .empty_values(false)
+                .help("chr.sizes bounding the results"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -52,19 +98,53 @@ List of operations
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap());
-    let is_multi: bool = yaml.values().next().unwrap().is_mapping();
-    let set_of = yaml2set_m(&yaml);
+    let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile").unwrap())?;
+    let is_multi: bool = !set_of.contains_key("__single");
 
     let op = args.value_of("op").unwrap();
-    let number: i32 = value_t!(args.value_of("number"), i32).unwrap_or_else(|e| {
-        eprintln!("Need a integer for --number\n{}", e);
-        std::process::exit(1)
-    });
+    let ops = [
+        "cover",
+        "holes",
+        "trim",
+        "pad",
+        "excise",
+        "fill",
+        "slop",
+        "flank",
+        "keep-largest",
+        "size-range",
+    ];
+    if !ops.contains(&op) {
+        return Err(Error::args(format!("Invalid IntSpan Op: {}", op)));
+    }
+    let number: i32 = value_t!(args.value_of("number"), i32)
+        .map_err(|_| Error::args("Need a integer for --number"))?;
+    let left: i32 = if args.is_present("left") {
+        value_t!(args.value_of("left"), i32)
+            .map_err(|_| Error::args("Need a integer for --left"))?
+    } else {
+        number
+    };
+    let right: i32 = if args.is_present("right") {
+        value_t!(args.value_of("right"), i32)
+            .map_err(|_| Error::args("Need a integer for --right"))?
+    } else {
+        number
+    };
+    let min: i32 =
+        value_t!(args.value_of("min"), i32).map_err(|_| Error::args("Need a integer for --min"))?;
+    let max: i32 = if args.is_present("max") {
+        value_t!(args.value_of("max"), i32).map_err(|_| Error::args("Need a integer for --max"))?
+    } else {
+        i32::MAX
+    };
+    let sizes: Option<BTreeMap<String, i32>> =
+        args.value_of("sizes").map(read_sizes).transpose()?;
 
     //----------------------------
     // Operating
@@ -73,16 +153,22 @@ pub fn execute(args: &ArgMatches) {
     for (name, set) in &set_of {
         let mut res: BTreeMap<String, IntSpan> = BTreeMap::new();
         for chr in set.keys() {
-            let intspan = match op {
+            let mut intspan = match op {
                 "cover" => set.get(chr).unwrap().cover(),
                 "holes" => set.get(chr).unwrap().holes(),
-                "trim" => set.get(chr).unwrap().trim(number),
-                "pad" => set.get(chr).unwrap().pad(number),
+                "trim" => set.get(chr).unwrap().trim_sides(left, right),
+                "pad" | "slop" => set.get(chr).unwrap().pad_sides(left, right),
                 "excise" => set.get(chr).unwrap().excise(number),
                 "fill" => set.get(chr).unwrap().fill(number),
-                _ => panic!("Invalid IntSpan Op"),
+                "flank" => set.get(chr).unwrap().flank(left, right),
+                "keep-largest" => set.get(chr).unwrap().largest(number.max(0) as usize),
+                "size-range" => set.get(chr).unwrap().spans_between(min, max),
+                _ => unreachable!(),
             };
             //            println!("Op {}: {}", op, op_intspan.to_string());
+            if let Some(size) = sizes.as_ref().and_then(|s| s.get(chr)) {
+                intspan = intspan.clip(1, *size);
+            }
             res.insert(chr.into(), intspan);
         }
         res_of.insert(name.into(), res);
@@ -94,7 +180,9 @@ pub fn execute(args: &ArgMatches) {
     let out_yaml = if is_multi {
         set2yaml_m(&res_of)
     } else {
-        set2yaml(&res_of.get("__single").unwrap())
+        set2yaml(res_of.get("__single").unwrap())
     };
-    write_yaml(args.value_of("outfile").unwrap(), &out_yaml);
+    write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+
+    Ok(())
 }
@@ -27,15 +27,16 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap());
+    let infile = args.value_of("infile").unwrap();
+    let yaml: BTreeMap<String, Value> = read_yaml(infile)?;
 
     let outdir = args.value_of("outdir").unwrap();
     if outdir != "stdout" {
-        fs::create_dir_all(outdir);
+        fs::create_dir_all(outdir).map_err(|e| Error::io(outdir, e))?;
     }
 
     //----------------------------
@@ -43,19 +44,21 @@ pub fn execute(args: &ArgMatches) {
     //----------------------------
     for (key, value) in &yaml {
         if !value.is_mapping() {
-            panic!("Not a valid multi-key runlist yaml file");
+            return Err(Error::parse(infile, 0, "Not a multi-name runlist file"));
         }
 
-        let string = serde_yaml::to_string(value).unwrap();
+        let string = serde_yaml::to_string(value)?;
 
         //----------------------------
         // Output
         //----------------------------
         if outdir == "stdout" {
-            write_lines("stdout", &vec![string.as_str()]);
+            write_lines("stdout", &vec![string.as_str()])?;
         } else {
             let path = Path::new(outdir).join(key.to_owned() + ".yml");
-            fs::write(path, string + "\n");
+            fs::write(&path, string + "\n").map_err(|e| Error::io(&path.to_string_lossy(), e))?;
         }
     }
+
+    Ok(())
 }
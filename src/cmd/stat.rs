@@ -1,6 +1,5 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use serde_yaml::Value;
 use std::collections::BTreeMap;
 
 // Create clap subcommand arguments
@@ -24,6 +23,14 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .long("all")
                 .help("Only write whole genome stats"),
         )
+        .arg(
+            Arg::with_name("order")
+                .long("order")
+                .takes_value(true)
+                .default_value("lexical")
+                .empty_values(false)
+                .help("Order of chromosomes: lexical, natural, roman, or a chr.sizes file"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -36,18 +43,19 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let sizes = read_sizes(args.value_of("chr.sizes").unwrap());
+    let sizes_file = args.value_of("chr.sizes").unwrap();
+    let sizes = read_sizes(sizes_file)?;
 
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile").unwrap());
-    let is_multi: bool = yaml.values().next().unwrap().is_mapping();
-
-    let set_of = yaml2set_m(&yaml);
+    let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile").unwrap())?;
+    let is_multi: bool = !set_of.contains_key("__single");
 
     let is_all = args.is_present("all");
+    let order = ChrOrder::parse(args.value_of("order").unwrap())?;
 
     //----------------------------
     // Operating
@@ -61,8 +69,9 @@ pub fn execute(args: &ArgMatches) {
         }
         lines.push(header);
 
-        for (name, set) in &set_of {
-            let key_lines = csv_lines(set, &sizes, is_all, Some(name));
+        for name in order.sorted(set_of.keys()) {
+            let key_lines = csv_lines(&set_of[name], &sizes, &order, is_all, Some(name))
+                .map_err(|e| e.in_file(sizes_file))?;
             lines.push(key_lines);
         }
     } else {
@@ -72,7 +81,8 @@ pub fn execute(args: &ArgMatches) {
         }
         lines.push(header);
 
-        let key_lines = csv_lines(set_of.get("__single").unwrap(), &sizes, is_all, None);
+        let key_lines = csv_lines(&set_of["__single"], &sizes, &order, is_all, None)
+            .map_err(|e| e.in_file(sizes_file))?;
         lines.push(key_lines);
     }
 
@@ -82,21 +92,26 @@ pub fn execute(args: &ArgMatches) {
     write_lines(
         args.value_of("outfile").unwrap(),
         &lines.iter().map(AsRef::as_ref).collect(),
-    );
+    )?;
+
+    Ok(())
 }
 
 fn csv_lines(
     set: &BTreeMap<String, IntSpan>,
     sizes: &BTreeMap<String, i32>,
+    order: &ChrOrder,
     is_all: bool,
     prefix: Option<&str>,
-) -> String {
+) -> Result<String> {
     let mut lines = String::new();
 
     let mut all_length = 0;
     let mut all_size = 0;
-    for chr in set.keys() {
-        let length = *sizes.get(chr).unwrap();
+    for chr in order.sorted(set.keys()) {
+        let length = *sizes
+            .get(chr)
+            .ok_or_else(|| Error::parse("", 0, format!("Chromosome {} is missing", chr)))?;
         let size = set.get(chr).unwrap().cardinality();
         let line = format!(
             "{},{},{},{:.4}\n",
@@ -132,5 +147,5 @@ fn csv_lines(
     lines.push_str(all_line.as_str());
 
     // Remove last LF, as write_lines will append one
-    lines.trim_end().to_string()
+    Ok(lines.trim_end().to_string())
 }
@@ -1,6 +1,5 @@
-use clap::*;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use serde_yaml::Value;
 use std::collections::{BTreeMap, BTreeSet};
 use std::ffi::OsStr;
 use std::path::Path;
@@ -47,6 +46,14 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .help("basename of infile2"),
         )
+        .arg(
+            Arg::with_name("order")
+                .long("order")
+                .takes_value(true)
+                .default_value("lexical")
+                .empty_values(false)
+                .help("Order of chromosomes: lexical, natural, roman, or a chr.sizes file"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -59,18 +66,18 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let sizes = read_sizes(args.value_of("chr.sizes").unwrap());
+    let sizes_file = args.value_of("chr.sizes").unwrap();
+    let sizes = read_sizes(sizes_file)?;
 
-    let yaml: BTreeMap<String, Value> = read_yaml(args.value_of("infile1").unwrap());
-    let is_multi: bool = yaml.values().next().unwrap().is_mapping();
-    let mut s1_of = yaml2set_m(&yaml);
+    let mut s1_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile1").unwrap())?;
+    let is_multi: bool = !s1_of.contains_key("__single");
 
-    let single: BTreeMap<String, Value> = read_yaml(args.value_of("infile2").unwrap());
-    let mut s2 = yaml2set(&single);
+    let mut s2: BTreeMap<String, IntSpan> = read_set(args.value_of("infile2").unwrap())?;
 
     let is_all = args.is_present("all");
     let base = if args.is_present("base") {
@@ -82,6 +89,7 @@ pub fn execute(args: &ArgMatches) {
             .unwrap()
     };
     let op = args.value_of("op").unwrap();
+    let order = ChrOrder::parse(args.value_of("order").unwrap())?;
 
     //----------------------------
     // Operating
@@ -102,7 +110,7 @@ pub fn execute(args: &ArgMatches) {
                 "diff" => s1.get(chr).unwrap().diff(s2.get(chr).unwrap()),
                 "union" => s1.get(chr).unwrap().union(s2.get(chr).unwrap()),
                 "xor" => s1.get(chr).unwrap().xor(s2.get(chr).unwrap()),
-                _ => panic!("Invalid IntSpan Op"),
+                _ => return Err(Error::args(format!("Invalid IntSpan Op: {}", op))),
             };
             //            println!("Op {}: {}", op, op_intspan.to_string());
             set.insert(chr.into(), intspan);
@@ -122,15 +130,17 @@ pub fn execute(args: &ArgMatches) {
         }
         lines.push(header);
 
-        for name in s1_of.keys() {
+        for name in order.sorted(s1_of.keys()) {
             let key_lines = csv_lines(
                 s1_of.get(name).unwrap(),
                 &sizes,
                 &s2,
                 res_of.get(name).unwrap(),
+                &order,
                 is_all,
                 Some(name),
-            );
+            )
+            .map_err(|e| e.in_file(sizes_file))?;
             lines.push(key_lines);
         }
     } else {
@@ -145,9 +155,11 @@ pub fn execute(args: &ArgMatches) {
             &sizes,
             &s2,
             res_of.get("__single").unwrap(),
+            &order,
             is_all,
             None,
-        );
+        )
+        .map_err(|e| e.in_file(sizes_file))?;
         lines.push(key_lines);
     }
 
@@ -157,7 +169,9 @@ pub fn execute(args: &ArgMatches) {
     write_lines(
         args.value_of("outfile").unwrap(),
         &lines.iter().map(AsRef::as_ref).collect(),
-    );
+    )?;
+
+    Ok(())
 }
 
 fn csv_lines(
@@ -165,17 +179,20 @@ fn csv_lines(
     sizes: &BTreeMap<String, i32>,
     s2: &BTreeMap<String, IntSpan>,
     set_op: &BTreeMap<String, IntSpan>,
+    order: &ChrOrder,
     is_all: bool,
     prefix: Option<&str>,
-) -> String {
+) -> Result<String> {
     let mut lines = String::new();
 
     let mut all_length = 0;
     let mut all_size = 0;
     let mut all_s2_length = 0;
     let mut all_s2_size = 0;
-    for chr in s1.keys() {
-        let length = *sizes.get(chr).unwrap();
+    for chr in order.sorted(s1.keys()) {
+        let length = *sizes
+            .get(chr)
+            .ok_or_else(|| Error::parse("", 0, format!("Chromosome {} is missing", chr)))?;
         let size = s1.get(chr).unwrap().cardinality();
 
         let s2_length = s2.get(chr).unwrap().cardinality();
@@ -235,5 +252,5 @@ fn csv_lines(
     lines.push_str(all_line.as_str());
 
     // Remove last LF, as write_lines will append one
-    lines.trim_end().to_string()
+    Ok(lines.trim_end().to_string())
 }
@@ -0,0 +1,123 @@
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
+use intspan::*;
+use std::collections::BTreeMap;
+
+// Create clap subcommand arguments
+pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("window")
+        .about("Split runlists into sliding or tiled windows")
+        .after_help(
+            "\
+Windows start at the first integer of each runlist and skip holes.
+With --members, --size and --step count integers in the runlist instead of
+chromosome positions, so a window may span several islands.
+
+Ranges are written as the covers of windows, names of multi-name YAML files are
+kept as in `name.I:1-100`. With --yaml, each window becomes a set named by its range. \
+            ",
+        )
+        .arg(
+            Arg::with_name("infile")
+                .help("Sets the input file to use")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("size")
+                .long("size")
+                .short("s")
+                .takes_value(true)
+                .required(true)
+                .help("Window size"),
+        )
+        .arg(
+            Arg::with_name("step")
+                .long("step")
+                .takes_value(true)
+                .help("Step between windows. Defaults to --size, tiled windows"),
+        )
+        .arg(
+            Arg::with_name("members")
+                .long("members")
+                .help("Measure windows in integers of runlists"),
+        )
+        .arg(
+            Arg::with_name("yaml")
+                .long("yaml")
+                .help("Write a YAML file instead of ranges"),
+        )
+        .arg(
+            Arg::with_name("outfile")
+                .short("o")
+                .long("outfile")
+                .takes_value(true)
+                .default_value("stdout")
+                .empty_values(false)
+                .help("Output filename. [stdout] for screen"),
+        )
+}
+
+// command implementation
+pub fn execute(args: &ArgMatches) -> Result<()> {
+    //----------------------------
+    // Loading
+    //----------------------------
+    let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+        read_set_m(args.value_of("infile").unwrap())?;
+
+    let size: i32 = value_t!(args.value_of("size"), i32)
+        .map_err(|_| Error::args("Need a integer for --size"))?;
+    let step: i32 = if args.is_present("step") {
+        value_t!(args.value_of("step"), i32)
+            .map_err(|_| Error::args("Need a integer for --step"))?
+    } else {
+        size
+    };
+    if size < 1 || step < 1 {
+        return Err(Error::args("--size and --step should be positive"));
+    }
+    let is_members = args.is_present("members");
+
+    //----------------------------
+    // Operating
+    //----------------------------
+    // window range => chr => IntSpan
+    let mut res_of: BTreeMap<String, BTreeMap<String, IntSpan>> = BTreeMap::new();
+    let mut lines: Vec<String> = vec![];
+    for (name, set) in &set_of {
+        for (chr, intspan) in set {
+            let windows = if is_members {
+                intspan.sliding_members(size, step)
+            } else {
+                intspan.sliding(size, step)
+            };
+
+            for window in windows {
+                let mut range = Range::from(chr.as_str(), window.min(), window.max()).to_string();
+                if name != "__single" {
+                    range = format!("{}.{}", name, range);
+                }
+
+                let mut res: BTreeMap<String, IntSpan> = BTreeMap::new();
+                res.insert(chr.to_string(), window);
+                res_of.insert(range.clone(), res);
+                lines.push(range);
+            }
+        }
+    }
+
+    //----------------------------
+    // Output
+    //----------------------------
+    if args.is_present("yaml") {
+        let out_yaml = set2yaml_m(&res_of);
+        write_yaml(args.value_of("outfile").unwrap(), &out_yaml)?;
+    } else {
+        write_lines(
+            args.value_of("outfile").unwrap(),
+            &lines.iter().map(AsRef::as_ref).collect(),
+        )?;
+    }
+
+    Ok(())
+}
@@ -23,6 +23,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .long("highlight")
                 .help("Create highlights instead of links"),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -35,12 +40,13 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let mut writer = writer(args.value_of("outfile").unwrap());
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
     let is_highlight = args.is_present("highlight");
+    let strict = args.is_present("strict");
 
     let mut colors = (1..=12)
         .map(|n| format!("paired-12-qual-{}", n).to_string())
@@ -49,30 +55,31 @@ pub fn execute(args: &ArgMatches) {
     let mut color_idx = 0;
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
-            let parts: Vec<&str> = line.split('\t').collect();
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
+            let ranges = line
+                .split('\t')
+                .map(|part| parse_range(part, infile, i + 1, strict))
+                .collect::<Result<Vec<Option<Range>>>>()?;
 
             if is_highlight {
-                for part in parts {
-                    let range = Range::from_str(part);
-                    if !range.is_valid() {
-                        continue;
-                    }
-
+                for range in ranges.iter().flatten() {
                     //----------------------------
                     // Output
                     //----------------------------
-                    writer.write_all(
-                        format!(
-                            "{} {} {} fill_color={}\n",
-                            range.chr(),
-                            range.start(),
-                            range.end(),
-                            colors[color_idx]
+                    writer
+                        .write_all(
+                            format!(
+                                "{} {} {} fill_color={}\n",
+                                range.chr(),
+                                range.start(),
+                                range.end(),
+                                colors[color_idx]
+                            )
+                            .as_ref(),
                         )
-                        .as_ref(),
-                    );
+                        .unwrap();
                 }
 
                 // rotate color
@@ -81,17 +88,17 @@ pub fn execute(args: &ArgMatches) {
                     color_idx = 0;
                 }
             } else {
-                let count = parts.len();
+                let count = ranges.len();
 
                 // 2-combinations of parts forms a pair
                 for i in 0..count {
                     'PAIR: for j in i + 1..count {
                         let mut fields: Vec<String> = vec![];
                         for idx in &[i, j] {
-                            let range = Range::from_str(parts[*idx]);
-                            if !range.is_valid() {
-                                continue 'PAIR;
-                            }
+                            let range = match &ranges[*idx] {
+                                Some(range) => range,
+                                None => continue 'PAIR,
+                            };
 
                             fields.push(range.chr().to_string());
                             if range.strand() == "-" {
@@ -106,10 +113,12 @@ pub fn execute(args: &ArgMatches) {
                         //----------------------------
                         // Output
                         //----------------------------
-                        writer.write_all(format!("{}\n", fields.join(" ")).as_ref());
+                        writer.write_all(format!("{}\n", fields.join(" ")).as_ref())?;
                     }
                 }
             }
         } // end of line
     }
+
+    Ok(())
 }
@@ -1,4 +1,4 @@
-use clap::*;
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
 use intspan::*;
 use petgraph::prelude::NodeIndex;
 use petgraph::*;
@@ -44,6 +44,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .short("v")
                 .help("Verbose mode"),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -56,15 +61,14 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let bundle: i32 = value_t!(args.value_of("bundle"), i32).unwrap_or_else(|e| {
-        eprintln!("Need a integer for --bundle\n{}", e);
-        std::process::exit(1)
-    });
+    let bundle: i32 = value_t!(args.value_of("bundle"), i32)
+        .map_err(|_| Error::args("Need a integer for --bundle"))?;
     let is_verbose = args.is_present("verbose");
+    let strict = args.is_present("strict");
 
     // cache ranges
     let mut range_of_part: HashMap<String, Range> = HashMap::new();
@@ -77,8 +81,13 @@ pub fn execute(args: &ArgMatches) {
         if is_verbose {
             eprintln!("==> Load replaces");
         }
-        for line in read_lines(args.value_of("replace").unwrap()) {
-            build_range_of_part(&line, &mut range_of_part);
+        let replace = args.value_of("replace").unwrap();
+        for (i, line) in read_lines(replace)?.into_iter().enumerate() {
+            for part in line.split('\t') {
+                if let Some(range) = parse_range(part, replace, i + 1, strict)? {
+                    range_of_part.insert(part.to_string(), range);
+                }
+            }
 
             let parts: Vec<&str> = line.split('\t').collect();
             if parts.len() == 2 {
@@ -96,9 +105,14 @@ pub fn execute(args: &ArgMatches) {
 
     let mut line_set: BTreeSet<String> = BTreeSet::new();
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
-            build_range_of_part(&line, &mut range_of_part);
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
+            for part in line.split('\t') {
+                if let Some(range) = parse_range(part, infile, i + 1, strict)? {
+                    range_of_part.insert(part.to_string(), range);
+                }
+            }
 
             let mut parts: Vec<String> = line.split('\t').map(String::from).collect();
             let count = parts.len();
@@ -158,7 +172,7 @@ pub fn execute(args: &ArgMatches) {
                 *range_1.strand_mut() = "-".to_string();
             }
 
-            let new_line = format!("{}\t{}", range_0.to_string(), range_1.to_string());
+            let new_line = format!("{}\t{}", range_0, range_1);
             build_range_of_part(&new_line, &mut range_of_part);
             line_set.insert(new_line);
         }
@@ -172,7 +186,7 @@ pub fn execute(args: &ArgMatches) {
         .iter()
         .map(String::to_string)
         .collect::<Vec<String>>();
-    lines = sort_links(&lines);
+    lines = sort_links(&lines, &ChrOrder::Lexical);
     let mut is_nested = true;
     while is_nested {
         if is_verbose {
@@ -226,7 +240,7 @@ pub fn execute(args: &ArgMatches) {
             .collect::<Vec<String>>();
         is_nested = !to_remove.is_empty();
     }
-    lines = sort_links(&lines);
+    lines = sort_links(&lines, &ChrOrder::Lexical);
 
     //----------------------------
     // Bundle links
@@ -303,7 +317,7 @@ pub fn execute(args: &ArgMatches) {
 
             // connected lines
             let mut line_list = connected_indices
-                .into_iter()
+                .iter()
                 .map(|idx| graph.node_weight(*idx).unwrap().clone())
                 .collect::<Vec<String>>();
             line_list.sort();
@@ -313,8 +327,7 @@ pub fn execute(args: &ArgMatches) {
 
             let mut merged_ranges: Vec<String> = Vec::new();
             for i in &[0, 1] {
-                let mut chr = "".to_string();
-                let mut strand = "".to_string();
+                let mut range = Range::new();
                 let mut intspan = IntSpan::new();
 
                 for line in &line_list {
@@ -326,15 +339,11 @@ pub fn execute(args: &ArgMatches) {
                         .collect::<Vec<String>>();
 
                     let parts: Vec<&str> = line.split('\t').collect();
-                    let range = range_of_part.get(parts[*i as usize]).unwrap();
-                    chr = range.chr().to_string();
-                    strand = range.strand().to_string();
+                    range = range_of_part[parts[*i as usize]].clone();
                     intspan.merge(&range.intspan());
                 }
 
-                let merged: String =
-                    format!("{}({}):{}-{}", chr, strand, intspan.min(), intspan.max());
-                merged_ranges.push(merged);
+                merged_ranges.push(range.with_intspan(&intspan).to_string());
             }
 
             let new_line = merged_ranges.join("\t");
@@ -345,7 +354,7 @@ pub fn execute(args: &ArgMatches) {
             lines.push(new_line);
         }
 
-        lines = sort_links(&lines);
+        lines = sort_links(&lines, &ChrOrder::Lexical);
     }
 
     //----------------------------
@@ -391,5 +400,7 @@ pub fn execute(args: &ArgMatches) {
     write_lines(
         args.value_of("outfile").unwrap(),
         &lines.iter().map(AsRef::as_ref).collect(),
-    );
+    )?;
+
+    Ok(())
 }
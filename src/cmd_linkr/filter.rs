@@ -1,6 +1,5 @@
-use clap::*;
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
 use intspan::*;
-use std::collections::BTreeSet;
 use std::io::BufRead;
 
 // Create clap subcommand arguments
@@ -34,6 +33,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .help("Ratio of lengths differences. The suggested value is [0.8]"),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -46,11 +50,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let mut writer = writer(args.value_of("outfile").unwrap());
+    let mut writer = writer(args.value_of("outfile").unwrap())?;
 
     let numbers = if args.is_present("number") {
         IntSpan::from(args.value_of("number").unwrap().to_string())
@@ -58,34 +62,30 @@ pub fn execute(args: &ArgMatches) {
         IntSpan::new()
     };
     let ratio: f32 = if args.is_present("ratio") {
-        value_t!(args.value_of("ratio"), f32).unwrap_or_else(|e| {
-            eprintln!("Need a float for --ratio\n{}", e);
-            std::process::exit(1)
-        })
+        value_t!(args.value_of("ratio"), f32)
+            .map_err(|_| Error::args("Need a float for --ratio"))?
     } else {
         -1.0
     };
+    let strict = args.is_present("strict");
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
             let parts: Vec<&str> = line.split('\t').collect();
 
-            if !numbers.is_empty() {
-                if !numbers.contains(parts.len() as i32) {
-                    continue;
-                }
+            if !numbers.is_empty() && !numbers.contains(parts.len() as i32) {
+                continue;
             }
 
             if ratio > 0.0 {
                 let mut lengths: Vec<i32> = vec![];
 
                 for part in &parts {
-                    let range = Range::from_str(*part);
-                    if !range.is_valid() {
-                        continue;
+                    if let Some(range) = parse_range(part, infile, i + 1, strict)? {
+                        lengths.push(range.intspan().cardinality());
                     }
-                    lengths.push(range.intspan().cardinality());
                 }
 
                 let min = lengths.iter().min().unwrap();
@@ -100,7 +100,9 @@ pub fn execute(args: &ArgMatches) {
             //----------------------------
             // Output
             //----------------------------
-            writer.write_all(format!("{}\n", line).as_ref());
+            writer.write_all(format!("{}\n", line).as_ref())?;
         } // end of line
     }
+
+    Ok(())
 }
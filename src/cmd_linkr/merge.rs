@@ -1,4 +1,4 @@
-use clap::*;
+use clap::{value_t, App, Arg, ArgMatches, SubCommand};
 use intspan::*;
 use petgraph::prelude::NodeIndex;
 use petgraph::*;
@@ -31,6 +31,11 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .short("v")
                 .help("Verbose mode"),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -43,15 +48,14 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
-    let coverage: f32 = value_t!(args.value_of("coverage"), f32).unwrap_or_else(|e| {
-        eprintln!("Need a float for --coverage\n{}", e);
-        std::process::exit(1)
-    });
+    let coverage: f32 = value_t!(args.value_of("coverage"), f32)
+        .map_err(|_| Error::args("Need a float for --coverage"))?;
     let is_verbose = args.is_present("verbose");
+    let strict = args.is_present("strict");
 
     // store graph separately by chromosomes
     // petgraph use NodeIndex to store and identify nodes
@@ -66,13 +70,14 @@ pub fn execute(args: &ArgMatches) {
     let mut chrs: HashSet<String> = HashSet::new();
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        for line in reader.lines().filter_map(|r| r.ok()) {
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
             for part in line.split('\t') {
-                let range = Range::from_str(part);
-                if !range.is_valid() {
-                    continue;
-                }
+                let range = match parse_range(part, infile, i + 1, strict)? {
+                    Some(range) => range,
+                    None => continue,
+                };
 
                 if range_of_part.contains_key(part) {
                     continue;
@@ -166,7 +171,7 @@ pub fn execute(args: &ArgMatches) {
 
             // connected ranges
             let mut part_list = connected_indices
-                .into_iter()
+                .iter()
                 .map(|idx| graph.node_weight(*idx).unwrap().clone())
                 .collect::<Vec<String>>();
             part_list.sort();
@@ -179,7 +184,7 @@ pub fn execute(args: &ArgMatches) {
             }
 
             // create merged range
-            let merged: String = format!("{}(+):{}", chr, intspan.to_string());
+            let merged: String = format!("{}(+):{}", chr, intspan);
 
             for part in &part_list {
                 if *part == merged {
@@ -201,5 +206,7 @@ pub fn execute(args: &ArgMatches) {
     write_lines(
         args.value_of("outfile").unwrap(),
         &out_lines.iter().map(AsRef::as_ref).collect(),
-    );
+    )?;
+
+    Ok(())
 }
@@ -14,6 +14,19 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .min_values(1)
                 .index(1),
         )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Abort on malformed ranges instead of skipping them"),
+        )
+        .arg(
+            Arg::with_name("order")
+                .long("order")
+                .takes_value(true)
+                .default_value("lexical")
+                .empty_values(false)
+                .help("Order of chromosomes: lexical, natural, roman, or a chr.sizes file"),
+        )
         .arg(
             Arg::with_name("outfile")
                 .short("o")
@@ -26,24 +39,29 @@ pub fn make_subcommand<'a, 'b>() -> App<'a, 'b> {
 }
 
 // command implementation
-pub fn execute(args: &ArgMatches) {
+pub fn execute(args: &ArgMatches) -> Result<()> {
     //----------------------------
     // Loading
     //----------------------------
+    let strict = args.is_present("strict");
+    let order = ChrOrder::parse(args.value_of("order").unwrap())?;
+
     let mut line_set: BTreeSet<String> = BTreeSet::new();
 
     for infile in args.values_of("infiles").unwrap() {
-        let reader = reader(infile);
-        'LINE: for line in reader.lines().filter_map(|r| r.ok()) {
-            let parts: Vec<&str> = line.split('\t').collect();
-
-            for part in parts {
-                let range = Range::from_str(part);
-                if range.is_valid() {
-                    line_set.insert(line.clone());
-                    continue 'LINE;
+        let reader = reader(infile)?;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Error::io(infile, e))?;
+
+            let mut is_link = false;
+            for part in line.split('\t') {
+                if parse_range(part, infile, i + 1, strict)?.is_some() {
+                    is_link = true;
                 }
             }
+            if is_link {
+                line_set.insert(line);
+            }
         } // end of line
     }
 
@@ -51,7 +69,7 @@ pub fn execute(args: &ArgMatches) {
     // Sorting
     //----------------------------
     let mut lines = line_set.into_iter().collect::<Vec<String>>();
-    lines = sort_links(&lines);
+    lines = sort_links(&lines, &order);
 
     //----------------------------
     // Output
@@ -59,5 +77,7 @@ pub fn execute(args: &ArgMatches) {
     write_lines(
         args.value_of("outfile").unwrap(),
         &lines.iter().map(AsRef::as_ref).collect(),
-    );
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,103 @@
+//! `Coord` abstracts over the integer types used as coordinates.
+//!
+//! `i32` is the default everywhere. Use `i64` (or `u64`) when positions go beyond ~2.1 Gbp, such
+//! as linear offsets into concatenated genomes.
+//!
+//! ```
+//! use intspan::{GenericIntSpan, IntSpan64};
+//!
+//! let set: IntSpan64 = "1-3000000000".parse().unwrap();
+//! assert_eq!(set.cardinality(), 3_000_000_000);
+//!
+//! let set: GenericIntSpan<u64> = "1-3000000000,5000000000".parse().unwrap();
+//! assert_eq!(set.span_size(), 2);
+//! ```
+
+use rand::distributions::uniform::SampleUniform;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::str::FromStr;
+
+pub trait Coord:
+    Copy
+    + Ord
+    + Hash
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + FromStr<Err = ParseIntError>
+    + From<u8>
+    + SampleUniform
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Send
+    + Sync
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Negative infinity, the smallest representable element. 0 for unsigned types, whose runlists
+    /// start at 1
+    const NEG_INF: Self;
+
+    /// Positive infinity as stored in edges. The largest representable element is `POS_INF - 1`
+    const POS_INF: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Lossless widening, used by the binary format
+    fn to_i128(self) -> i128;
+
+    /// `None` if `n` doesn't fit
+    fn from_i128(n: i128) -> Option<Self>;
+}
+
+macro_rules! impl_coord {
+    ($t:ty, $neg_inf:expr) => {
+        impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const NEG_INF: Self = $neg_inf;
+            const POS_INF: Self = <$t>::MAX - 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$t>::saturating_add(self, rhs)
+            }
+            fn saturating_sub(self, rhs: Self) -> Self {
+                <$t>::saturating_sub(self, rhs)
+            }
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+            fn from_i128(n: i128) -> Option<Self> {
+                <$t>::try_from(n).ok()
+            }
+        }
+    };
+}
+
+impl_coord!(i32, i32::MIN + 1);
+impl_coord!(i64, i64::MIN + 1);
+// 0 stands for negative infinity, so unsigned coordinates start at 1
+impl_coord!(u32, 0);
+impl_coord!(u64, 0);
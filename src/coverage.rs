@@ -1,27 +1,37 @@
-use crate::IntSpan;
+use crate::{Coord, GenericIntSpan};
 use std::collections::BTreeMap;
 
 #[derive(Default, Clone)]
-pub struct Coverage {
+pub struct GenericCoverage<T> {
     max: i32,
-    tiers: BTreeMap<i32, IntSpan>,
+    tiers: BTreeMap<i32, GenericIntSpan<T>>,
 }
 
-impl Coverage {
+/// `Coverage` with `i32` coordinates, the default one
+pub type Coverage = GenericCoverage<i32>;
+
+/// `Coverage` with `i64` coordinates
+pub type Coverage64 = GenericCoverage<i64>;
+
+impl<T: Coord> GenericCoverage<T> {
     pub fn max(&self) -> &i32 {
         &self.max
     }
-    pub fn tiers(&self) -> &BTreeMap<i32, IntSpan> {
+    pub fn tiers(&self) -> &BTreeMap<i32, GenericIntSpan<T>> {
         &self.tiers
     }
 
     pub fn new(max: i32) -> Self {
-        let mut tiers: BTreeMap<i32, IntSpan> = BTreeMap::new();
-        tiers.insert(-1, IntSpan::from("1-1000000000"));
-        tiers.insert(0, IntSpan::from("1-1000000000"));
+        // all positive positions
+        let mut universe = GenericIntSpan::new();
+        universe.add_pair(T::ONE, universe.get_pos_inf());
+
+        let mut tiers: BTreeMap<i32, GenericIntSpan<T>> = BTreeMap::new();
+        tiers.insert(-1, universe.clone());
+        tiers.insert(0, universe);
 
         for i in 1..=max {
-            tiers.insert(i, IntSpan::new());
+            tiers.insert(i, GenericIntSpan::new());
         }
 
         Self { max, tiers }
@@ -33,10 +43,10 @@ impl Coverage {
     /// cover.bump(1, 100);
     /// cover.bump(90, 150);
     /// assert_eq!(cover.tiers().get(&1).unwrap().to_string(), "1-150");
-    /// # assert_eq!(cover.tiers().get(&0).unwrap().to_string(), "151-1000000000");
+    /// # assert_eq!(cover.tiers().get(&0).unwrap().to_string(), format!("151-{}", i32::MAX - 2));
     /// ```
-    pub fn bump(&mut self, start: i32, end: i32) {
-        let mut intspan = IntSpan::new();
+    pub fn bump(&mut self, start: T, end: T) {
+        let mut intspan = GenericIntSpan::new();
         intspan.add_pair(start, end);
 
         // reach max coverage in full sequence
@@ -60,7 +70,7 @@ impl Coverage {
                 break;
             }
 
-            intspan = intersect.copy();
+            intspan = intersect;
         }
     }
 
@@ -76,7 +86,7 @@ impl Coverage {
     /// cover.bump(90, 150);
     /// assert_eq!(cover.max_tier().to_string(), "-");
     /// ```
-    pub fn max_tier(&self) -> IntSpan {
-        self.tiers().get(self.max()).unwrap().copy()
+    pub fn max_tier(&self) -> GenericIntSpan<T> {
+        self.tiers().get(self.max()).unwrap().clone()
     }
 }
@@ -0,0 +1,159 @@
+//! Errors of the library functions and of the subcommands.
+//!
+//! ```
+//! use intspan::Error;
+//!
+//! let err = intspan::read_sizes("tests/file/doesnt/exist").err().unwrap();
+//! assert!(matches!(err, Error::Io { .. }));
+//! assert!(err.to_string().starts_with("tests/file/doesnt/exist: "));
+//! assert_eq!(err.exit_code(), 3);
+//! ```
+
+use crate::{BinError, ExprError, ParseError};
+use std::fmt;
+use std::io;
+
+/// `Result` with the crate's `Error` as the default error type
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while reading inputs, writing outputs or interpreting arguments
+#[derive(Debug)]
+pub enum Error {
+    /// A file can't be opened, read or written. `path` is empty when unknown
+    Io { path: String, source: io::Error },
+    /// Malformed contents of a file. `path` is empty when unknown, `line` is 1-based, 0 when unknown
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+    /// Invalid values of command-line arguments
+    Args(String),
+    /// A malformed expression, see `Expr`
+    Expr { expr: String, source: ExprError },
+}
+
+impl Error {
+    pub fn io(path: &str, source: io::Error) -> Self {
+        Error::Io {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    pub fn parse<S: Into<String>>(path: &str, line: usize, message: S) -> Self {
+        Error::Parse {
+            path: path.to_string(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    pub fn args<S: Into<String>>(message: S) -> Self {
+        Error::Args(message.into())
+    }
+
+    pub fn expr(expr: &str, source: ExprError) -> Self {
+        Error::Expr {
+            expr: expr.to_string(),
+            source,
+        }
+    }
+
+    /// Sets the path of errors raised where it wasn't known
+    pub fn in_file(mut self, file: &str) -> Self {
+        match &mut self {
+            Error::Io { path, .. } | Error::Parse { path, .. } if path.is_empty() => {
+                *path = file.to_string();
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Process exit code, distinct for each kind. clap exits with 1 on its own errors
+    ///
+    /// * 2 - invalid arguments or expressions
+    /// * 3 - IO errors
+    /// * 4 - malformed inputs
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Args(_) | Error::Expr { .. } => 2,
+            Error::Io { .. } => 3,
+            Error::Parse { .. } => 4,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } if path.is_empty() => write!(f, "{}", source),
+            Error::Io { path, source } => write!(f, "{}: {}", path, source),
+            Error::Parse {
+                path,
+                line: 0,
+                message,
+            } if path.is_empty() => write!(f, "{}", message),
+            Error::Parse {
+                path,
+                line,
+                message,
+            } if path.is_empty() => write!(f, "line {}: {}", line, message),
+            Error::Parse {
+                path,
+                line: 0,
+                message,
+            } => write!(f, "{}: {}", path, message),
+            Error::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path, line, message),
+            Error::Args(message) => write!(f, "{}", message),
+            // points at the column of the mistake
+            Error::Expr { expr, source } => write!(
+                f,
+                "{}\n    {}\n    {}^",
+                source,
+                expr,
+                " ".repeat(source.column() - 1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Expr { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(source: io::Error) -> Self {
+        Error::io("", source)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(err: serde_yaml::Error) -> Self {
+        let line = err.location().map(|l| l.line()).unwrap_or(0);
+        Error::parse("", line, err.to_string())
+    }
+}
+
+impl From<BinError> for Error {
+    fn from(err: BinError) -> Self {
+        Error::parse("", 0, err.to_string())
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::parse("", 0, err.to_string())
+    }
+}
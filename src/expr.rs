@@ -0,0 +1,598 @@
+//! Expressions composing set and span operations over named sets.
+//!
+//! ```
+//! use intspan::{Expr, IntSpan};
+//! use std::collections::BTreeMap;
+//!
+//! let mut sets: BTreeMap<&str, IntSpan> = BTreeMap::new();
+//! sets.insert("exons", IntSpan::from("100-200,300-400"));
+//! sets.insert("repeat", IntSpan::from("150-350"));
+//! sets.insert("genes", IntSpan::from("1000-2000"));
+//!
+//! let expr = Expr::parse("exons & !repeat | genes.pad(10) - genes").unwrap();
+//! let set = expr.eval(&|name| sets.get(name)).unwrap();
+//! assert_eq!(set.to_string(), "100-149,351-400,990-999,2001-2010");
+//! ```
+//!
+//! Operators, loosest first, are `|` union, `^` xor, `&` intersect and `-` diff, then the
+//! prefix `!` complement. Span ops are called as methods: `pad(n)`, `trim(n)`, `inset(n)`,
+//! `fill(n)`, `excise(n)`, `holes()` and `cover()`. Names are letters, digits and `_`, or any
+//! text in double quotes, like `"AT1G01010.1"`.
+
+use crate::{Coord, GenericIntSpan};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// A parsed expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Name {
+        name: String,
+        column: usize,
+    },
+    Not(Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Span {
+        op: SpanOp,
+        arg: Option<i128>,
+        target: Box<Expr>,
+        column: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Union,
+    Xor,
+    Intersect,
+    Diff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanOp {
+    Pad,
+    Trim,
+    Inset,
+    Fill,
+    Excise,
+    Holes,
+    Cover,
+}
+
+impl SpanOp {
+    fn from_name(name: &str) -> Option<Self> {
+        let op = match name {
+            "pad" => SpanOp::Pad,
+            "trim" => SpanOp::Trim,
+            "inset" => SpanOp::Inset,
+            "fill" => SpanOp::Fill,
+            "excise" => SpanOp::Excise,
+            "holes" => SpanOp::Holes,
+            "cover" => SpanOp::Cover,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn takes_arg(self) -> bool {
+        !matches!(self, SpanOp::Holes | SpanOp::Cover)
+    }
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Self, ExprError> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.union()?;
+        match parser.peek() {
+            (Token::End, _) => Ok(expr),
+            (token, column) => Err(ExprError::new(
+                ExprErrorKind::UnexpectedToken,
+                column,
+                &token.to_string(),
+            )),
+        }
+    }
+
+    /// Names with the columns where they first appear
+    pub fn names(&self) -> BTreeMap<&str, usize> {
+        let mut names: BTreeMap<&str, usize> = BTreeMap::new();
+        self.walk_names(&mut names);
+        names
+    }
+
+    fn walk_names<'a>(&'a self, names: &mut BTreeMap<&'a str, usize>) {
+        match self {
+            Expr::Name { name, column } => {
+                let first = names.entry(name.as_str()).or_insert(*column);
+                *first = (*first).min(*column);
+            }
+            Expr::Not(inner) => inner.walk_names(names),
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.walk_names(names);
+                rhs.walk_names(names);
+            }
+            Expr::Span { target, .. } => target.walk_names(names),
+        }
+    }
+
+    /// Evaluates with sets found by `lookup`
+    pub fn eval<'a, T, F>(&self, lookup: &F) -> Result<GenericIntSpan<T>, ExprError>
+    where
+        T: Coord + 'a,
+        F: Fn(&str) -> Option<&'a GenericIntSpan<T>>,
+    {
+        let set = match self {
+            Expr::Name { name, column } => lookup(name)
+                .ok_or_else(|| ExprError::new(ExprErrorKind::UnknownName, *column, name))?
+                .clone(),
+            Expr::Not(inner) => inner.eval(lookup)?.complement(),
+            Expr::Binary { op, lhs, rhs } => {
+                let lhs = lhs.eval(lookup)?;
+                let rhs = rhs.eval(lookup)?;
+                match op {
+                    BinaryOp::Union => lhs.union(&rhs),
+                    BinaryOp::Xor => lhs.xor(&rhs),
+                    BinaryOp::Intersect => lhs.intersect(&rhs),
+                    BinaryOp::Diff => lhs.diff(&rhs),
+                }
+            }
+            Expr::Span {
+                op,
+                arg,
+                target,
+                column,
+            } => {
+                let set = target.eval(lookup)?;
+                let n = match arg {
+                    Some(arg) => T::from_i128(*arg).ok_or_else(|| {
+                        ExprError::new(ExprErrorKind::Overflow, *column, &arg.to_string())
+                    })?,
+                    None => T::ZERO,
+                };
+                match op {
+                    SpanOp::Pad => set.pad(n),
+                    SpanOp::Trim => set.trim(n),
+                    SpanOp::Inset => set.inset(n),
+                    SpanOp::Fill => set.fill(n),
+                    SpanOp::Excise => set.excise(n),
+                    SpanOp::Holes => set.holes(),
+                    SpanOp::Cover => set.cover(),
+                }
+            }
+        };
+
+        Ok(set)
+    }
+
+    /// Evaluates chromosome by chromosome over sets of the form `yaml2set_m()` returns. A
+    /// chromosome missing from a set counts as empty there
+    ///
+    /// ```
+    /// # use intspan::*;
+    /// # use std::collections::BTreeMap;
+    /// let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+    ///     read_set_m("tests/resources/I.II.yml").unwrap();
+    /// let expr = Expr::parse("I | II.trim(10)").unwrap();
+    /// let res = expr.eval_map(&set_of).unwrap();
+    /// assert_eq!(res.keys().collect::<Vec<_>>(), vec!["I", "II"]);
+    ///
+    /// let err = Expr::parse("I | III").unwrap().eval_map(&set_of).err().unwrap();
+    /// assert_eq!(err.to_string(), "Unknown name `III` at column 5");
+    /// ```
+    pub fn eval_map<T: Coord>(
+        &self,
+        set_of: &BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
+    ) -> Result<BTreeMap<String, GenericIntSpan<T>>, ExprError> {
+        let mut chrs: BTreeSet<&String> = BTreeSet::new();
+        for (name, column) in self.names() {
+            let set = set_of
+                .get(name)
+                .ok_or_else(|| ExprError::new(ExprErrorKind::UnknownName, column, name))?;
+            chrs.extend(set.keys());
+        }
+
+        let empty = GenericIntSpan::new();
+        let mut res: BTreeMap<String, GenericIntSpan<T>> = BTreeMap::new();
+        for chr in chrs {
+            let lookup = |name: &str| set_of.get(name).map(|set| set.get(chr).unwrap_or(&empty));
+            res.insert(chr.to_string(), self.eval(&lookup)?);
+        }
+
+        Ok(res)
+    }
+}
+
+//----------------------------------------------------------
+// Tokenizer
+//----------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    Int(i128),
+    Op(char),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Int(n) => write!(f, "{}", n),
+            Token::Op(c) => write!(f, "{}", c),
+            Token::End => write!(f, "end"),
+        }
+    }
+}
+
+// Tokens with their 1-based columns
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, ExprError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens: Vec<(Token, usize)> = vec![];
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if "|^&-!.(),".contains(c) {
+            tokens.push((Token::Op(c), column));
+            i += 1;
+        } else if c == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&c| c == '"')
+                .ok_or_else(|| ExprError::new(ExprErrorKind::UnterminatedName, column, "\""))?;
+            let name: String = chars[i + 1..i + 1 + end].iter().collect();
+            tokens.push((Token::Name(name), column));
+            i += end + 2;
+        } else if c.is_ascii_digit() {
+            let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+            let digits: String = chars[i..i + len].iter().collect();
+            let n = digits
+                .parse::<i128>()
+                .map_err(|_| ExprError::new(ExprErrorKind::Overflow, column, &digits))?;
+            tokens.push((Token::Int(n), column));
+            i += len;
+        } else if c.is_alphabetic() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .count();
+            tokens.push((Token::Name(chars[i..i + len].iter().collect()), column));
+            i += len;
+        } else {
+            return Err(ExprError::new(
+                ExprErrorKind::UnexpectedChar,
+                column,
+                &c.to_string(),
+            ));
+        }
+    }
+    tokens.push((Token::End, chars.len() + 1));
+
+    Ok(tokens)
+}
+
+//----------------------------------------------------------
+// Parser
+//----------------------------------------------------------
+
+// Recursive descent, one function per precedence level
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> (Token, usize) {
+        self.tokens[self.pos].clone()
+    }
+
+    fn next(&mut self) -> (Token, usize) {
+        let token = self.peek();
+        if token.0 != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek().0 == Token::Op(op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: char) -> Result<(), ExprError> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            Err(unexpected(self.peek()))
+        }
+    }
+
+    fn binary<F>(&mut self, op: char, kind: BinaryOp, operand: F) -> Result<Expr, ExprError>
+    where
+        F: Fn(&mut Self) -> Result<Expr, ExprError>,
+    {
+        let mut lhs = operand(self)?;
+        while self.eat(op) {
+            let rhs = operand(self)?;
+            lhs = Expr::Binary {
+                op: kind,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn union(&mut self) -> Result<Expr, ExprError> {
+        self.binary('|', BinaryOp::Union, Self::xor)
+    }
+
+    fn xor(&mut self) -> Result<Expr, ExprError> {
+        self.binary('^', BinaryOp::Xor, Self::intersect)
+    }
+
+    fn intersect(&mut self) -> Result<Expr, ExprError> {
+        self.binary('&', BinaryOp::Intersect, Self::diff)
+    }
+
+    fn diff(&mut self) -> Result<Expr, ExprError> {
+        self.binary('-', BinaryOp::Diff, Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        if self.eat('!') {
+            Ok(Expr::Not(Box::new(self.unary()?)))
+        } else {
+            self.postfix()
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ExprError> {
+        let mut expr = self.primary()?;
+        while self.eat('.') {
+            let (token, column) = self.next();
+            let name = match token {
+                Token::Name(name) => name,
+                _ => return Err(unexpected((token, column))),
+            };
+            let op = SpanOp::from_name(&name)
+                .ok_or_else(|| ExprError::new(ExprErrorKind::UnknownOp, column, &name))?;
+
+            self.expect('(')?;
+            let mut args: Vec<i128> = vec![];
+            if !self.eat(')') {
+                loop {
+                    args.push(self.int()?);
+                    if self.eat(')') {
+                        break;
+                    }
+                    self.expect(',')?;
+                }
+            }
+            if args.len() != op.takes_arg() as usize {
+                return Err(ExprError::new(ExprErrorKind::BadArguments, column, &name));
+            }
+
+            expr = Expr::Span {
+                op,
+                arg: args.first().copied(),
+                target: Box::new(expr),
+                column,
+            };
+        }
+        Ok(expr)
+    }
+
+    fn int(&mut self) -> Result<i128, ExprError> {
+        let negative = self.eat('-');
+        match self.next() {
+            (Token::Int(n), _) if negative => Ok(-n),
+            (Token::Int(n), _) => Ok(n),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        match self.next() {
+            (Token::Name(name), column) => Ok(Expr::Name { name, column }),
+            (Token::Op('('), _) => {
+                let expr = self.union()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            other => Err(unexpected(other)),
+        }
+    }
+}
+
+fn unexpected((token, column): (Token, usize)) -> ExprError {
+    match token {
+        Token::End => ExprError::new(ExprErrorKind::UnexpectedEnd, column, ""),
+        token => ExprError::new(ExprErrorKind::UnexpectedToken, column, &token.to_string()),
+    }
+}
+
+//----------------------------------------------------------
+// ExprError
+//----------------------------------------------------------
+
+/// The kind of error encountered while parsing or evaluating an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprErrorKind {
+    /// A character that starts no token
+    UnexpectedChar,
+    /// A token out of place
+    UnexpectedToken,
+    /// The expression ends early
+    UnexpectedEnd,
+    /// A quoted name without the closing quote
+    UnterminatedName,
+    /// A name not bound to any set
+    UnknownName,
+    /// A method other than the span ops
+    UnknownOp,
+    /// A span op with the wrong number of arguments
+    BadArguments,
+    /// A number beyond the coordinate type
+    Overflow,
+}
+
+/// Error returned by `Expr::parse()` and `Expr::eval()`
+///
+/// ```
+/// # use intspan::{Expr, ExprErrorKind};
+/// let err = Expr::parse("exons & (repeat | ").err().unwrap();
+/// assert_eq!(err.kind(), ExprErrorKind::UnexpectedEnd);
+/// assert_eq!(err.column(), 19);
+///
+/// let err = Expr::parse("exons.pad()").err().unwrap();
+/// assert_eq!(err.to_string(), "Wrong number of arguments to `pad` at column 7");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    kind: ExprErrorKind,
+    column: usize,
+    token: String,
+}
+
+impl ExprError {
+    fn new(kind: ExprErrorKind, column: usize, token: &str) -> Self {
+        Self {
+            kind,
+            column,
+            token: token.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> ExprErrorKind {
+        self.kind
+    }
+
+    /// 1-based column, in characters, of the mistake
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The offending token or name
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ExprErrorKind::UnexpectedChar => "Unexpected character",
+            ExprErrorKind::UnexpectedToken => "Unexpected",
+            ExprErrorKind::UnexpectedEnd => "Unexpected end of expression",
+            ExprErrorKind::UnterminatedName => "Unterminated quoted name",
+            ExprErrorKind::UnknownName => "Unknown name",
+            ExprErrorKind::UnknownOp => "Unknown span op",
+            ExprErrorKind::BadArguments => "Wrong number of arguments to",
+            ExprErrorKind::Overflow => "Number overflow",
+        };
+        match self.kind {
+            ExprErrorKind::UnexpectedEnd | ExprErrorKind::UnterminatedName => {
+                write!(f, "{} at column {}", what, self.column)
+            }
+            _ => write!(f, "{} `{}` at column {}", what, self.token, self.column),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntSpan;
+
+    fn sets() -> BTreeMap<&'static str, IntSpan> {
+        let mut sets = BTreeMap::new();
+        sets.insert("a", IntSpan::from("1-10"));
+        sets.insert("b", IntSpan::from("5-15"));
+        sets.insert("c", IntSpan::from("8-20,30-31,40-50"));
+        sets.insert("AT1G01010.1", IntSpan::from("100-200"));
+        sets
+    }
+
+    #[test]
+    fn test_eval() {
+        let sets = sets();
+        let tests = vec![
+            ("a", "1-10"),
+            ("a | b", "1-15"),
+            ("a & b", "5-10"),
+            ("a - b", "1-4"),
+            ("a ^ b", "1-4,11-15"),
+            ("a & !b", "1-4"),
+            // `-` binds tighter than `&`, `&` than `^`, `^` than `|`
+            ("a | b & c", "1-15"),
+            ("(a | b) & c", "8-15"),
+            ("c - a & b", "11-15"),
+            ("a ^ b | c", "1-4,8-20,30-31,40-50"),
+            ("!!a", "1-10"),
+            ("a.pad(2)", "-1-12"),
+            ("a.trim(2)", "3-8"),
+            ("a.inset(2)", "3-8"),
+            ("a.pad(-2)", "3-8"),
+            ("c.holes()", "21-29,32-39"),
+            ("c.cover()", "8-50"),
+            ("c.fill(10)", "8-50"),
+            ("c.excise(3)", "8-20,40-50"),
+            ("c.fill(8).excise(20)", "30-50"),
+            ("!c.cover() & a", "1-7"),
+            ("\"AT1G01010.1\".pad(1) - a", "99-201"),
+        ];
+
+        for (src, expected) in tests {
+            let expr = Expr::parse(src).unwrap();
+            let set = expr.eval(&|name| sets.get(name)).unwrap();
+            assert_eq!(set.to_string(), expected, "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let sets = sets();
+        let tests = vec![
+            ("", ExprErrorKind::UnexpectedEnd, 1),
+            ("a |", ExprErrorKind::UnexpectedEnd, 4),
+            ("a b", ExprErrorKind::UnexpectedToken, 3),
+            ("(a | b", ExprErrorKind::UnexpectedEnd, 7),
+            ("a | b)", ExprErrorKind::UnexpectedToken, 6),
+            ("a & # b", ExprErrorKind::UnexpectedChar, 5),
+            ("a | \"b", ExprErrorKind::UnterminatedName, 5),
+            ("a.grow(1)", ExprErrorKind::UnknownOp, 3),
+            ("a.pad", ExprErrorKind::UnexpectedEnd, 6),
+            ("a.pad(1, 2)", ExprErrorKind::BadArguments, 3),
+            ("a.holes(1)", ExprErrorKind::BadArguments, 3),
+            ("a.pad(b)", ExprErrorKind::UnexpectedToken, 7),
+            ("a.(1)", ExprErrorKind::UnexpectedToken, 3),
+            ("a | d", ExprErrorKind::UnknownName, 5),
+            ("a.pad(99999999999)", ExprErrorKind::Overflow, 3),
+        ];
+
+        for (src, kind, column) in tests {
+            let err = Expr::parse(src)
+                .and_then(|expr| expr.eval(&|name| sets.get(name)))
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), kind, "{}", src);
+            assert_eq!(err.column(), column, "{}", src);
+        }
+    }
+}
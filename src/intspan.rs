@@ -42,6 +42,9 @@
 //! binary search for many internal operations so that overall performance tends towards O log N where N
 //! is the number of ranges.
 //!
+//! Sets of many small islands, like SNP positions, are better kept in a `Bitmap`. `AutoSet` picks
+//! between the two by island density.
+//!
 //! The internal representation used by this module is extremely simple: a set is represented as a list
 //! of integers. Integers in even numbered positions (0, 2, 4 etc) represent the start of a run of
 //! numbers while those in odd numbered positions represent the ends of runs. As an example the set (1,
@@ -82,56 +85,132 @@
 //! # assert_eq!(set.is_neg_inf(), false);
 //! ```
 //!
+//! `IntSpan` is an alias of `GenericIntSpan<i32>`. `IntSpan64` and `GenericIntSpan<u64>` share
+//! all the same methods, with their infinities taken from the wider types.
+//!
+//! Unsigned types have nothing below 0, so 0 is their negative infinity and coordinates start at 1.
+//! Runlists with 0 are rejected:
+//!
+//! ```
+//! # use intspan::{GenericIntSpan, ParseErrorKind};
+//! # use std::convert::TryFrom;
+//! let err = GenericIntSpan::<u64>::try_from("0-5").err().unwrap();
+//! assert_eq!(err.kind(), ParseErrorKind::Overflow);
+//!
+//! let set = GenericIntSpan::<u64>::try_from("1-5").unwrap();
+//! assert!(set.is_finite());
+//! assert_eq!(set.complement().to_string(), format!("0,6-{}", set.get_pos_inf()));
+//! ```
+//!
 //! This Rust crate is ported from the Java class `jintspan` and the Perl module `AlignDB::IntSpan`,
 //! which contains many codes from `Set::IntSpan`, `Set::IntSpan::Fast` and `Set::IntSpan::Island`.
 //!
 
+use crate::coord::Coord;
+use crate::similarity::Similarity;
+use rand::Rng;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
+use std::str::FromStr;
 use std::vec::Vec;
 
+/// Edges are kept normalized, so two sets are equal, or hash the same, exactly when they contain the
+/// same elements.
 #[derive(Default, Clone)]
-pub struct IntSpan {
-    edges: Vec<i32>,
-    pos_inf: i32,
-    neg_inf: i32,
-    empty_string: String,
+pub struct GenericIntSpan<T> {
+    edges: Vec<T>,
+    // Numbers of elements before each span, built by `build_index()` and dropped on mutation
+    prefix: Option<Vec<T>>,
 }
 
+/// `IntSpan` with `i32` coordinates, the default one
+pub type IntSpan = GenericIntSpan<i32>;
+
+/// `IntSpan` with `i64` coordinates, for positions beyond ~2.1 Gbp
+pub type IntSpan64 = GenericIntSpan<i64>;
+
+const EMPTY_STRING: &str = "-";
+
 //----------------------------------------------------------
 // Set contents
 //----------------------------------------------------------
 
-impl IntSpan {
+impl<T: Coord> GenericIntSpan<T> {
     pub fn new() -> Self {
-        IntSpan {
+        Self {
             edges: Vec::new(),
-            pos_inf: 2_147_483_647 - 1, // INT_MAX - 1, Real Largest int is POS_INF - 1
-            neg_inf: -2_147_483_648 + 1, // INT_MIN + 1
-            empty_string: "-".to_string(),
+            prefix: None,
+        }
+    }
+
+    /// Builds a set from `(lower, upper)` pairs in any order, sorting and coalescing them in
+    /// O(n log n). Panics on pairs with lower larger than upper, or beyond the infinities
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from_pairs(vec![(20, 30), (1, 5), (6, 8), (25, 40), (3, 4)]);
+    /// assert_eq!(set.to_string(), "1-8,20-40");
+    /// ```
+    pub fn from_pairs<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let mut pairs: Vec<(T, T)> = pairs.into_iter().collect();
+        for &(lower, upper) in &pairs {
+            Self::check_pair(lower, upper);
+        }
+        pairs.sort_unstable();
+
+        let mut edges: Vec<T> = Vec::with_capacity(pairs.len() * 2);
+        for (lower, upper) in pairs {
+            // edges are half-open, the last one is one past the current upper
+            match edges.last_mut() {
+                Some(last) if lower <= *last => {
+                    if upper >= *last {
+                        *last = upper + T::ONE;
+                    }
+                }
+                _ => {
+                    edges.push(lower);
+                    edges.push(upper + T::ONE);
+                }
+            }
+        }
+
+        Self {
+            edges,
+            prefix: None,
         }
     }
 
+    /// Panics on malformed runlists, see `IntSpan::try_from()` for the fallible version
     pub fn from<S>(runlist: S) -> Self
     where
         S: Into<String>,
     {
         let s = runlist.into();
 
-        let mut new = Self::new();
-        new.add_runlist(s);
-
-        new
+        Self::try_from(s.as_str()).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    pub fn get_neg_inf(&self) -> i32 {
-        self.neg_inf.clone()
+    pub fn get_neg_inf(&self) -> T {
+        T::NEG_INF
     }
 
-    pub fn get_pos_inf(&self) -> i32 {
-        self.pos_inf.clone() - 1
+    pub fn get_pos_inf(&self) -> T {
+        T::POS_INF - T::ONE
     }
 
     pub fn clear(&mut self) {
+        self.prefix = None;
         self.edges.clear();
     }
 
@@ -143,57 +222,30 @@ impl IntSpan {
         self.edge_size() / 2
     }
 
-    pub fn to_string(&self) -> String {
-        if self.is_empty() {
-            return self.empty_string.clone();
-        }
-
-        let mut runlist = "".to_string();
-
-        for i in 0..self.span_size() {
-            let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
-
-            let mut buf = "".to_string();
-            if i != 0 {
-                buf.push_str(",");
-            }
-
-            if lower == upper {
-                buf.push_str(lower.to_string().as_str());
-            } else {
-                buf.push_str(format!("{}-{}", lower, upper).as_str());
-            }
-
-            runlist.push_str(buf.as_str());
-        }
-
-        runlist
-    }
-
-    pub fn to_vec(&self) -> Vec<i32> {
-        let mut elements: Vec<i32> = Vec::new();
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut elements: Vec<T> = Vec::new();
 
         for i in 0..self.span_size() {
             let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
+            let upper = *self.edges.get(i * 2 + 1).unwrap() - T::ONE;
 
-            let span_len = upper - lower + 1;
-            for j in 0..span_len {
-                elements.push(lower + j);
+            let mut j = lower;
+            while j <= upper {
+                elements.push(j);
+                j += T::ONE;
             }
         }
 
         elements
     }
 
-    pub fn ranges(&self) -> Vec<i32> {
-        let mut ranges: Vec<i32> = Vec::new();
+    pub fn ranges(&self) -> Vec<T> {
+        let mut ranges: Vec<T> = Vec::new();
 
         for i in 0..self.edges.len() {
             // odd index means upper
             if (i & 1) == 1 {
-                ranges.push(*self.edges.get(i).unwrap() - 1);
+                ranges.push(*self.edges.get(i).unwrap() - T::ONE);
             } else {
                 ranges.push(*self.edges.get(i).unwrap());
             }
@@ -202,25 +254,39 @@ impl IntSpan {
         ranges
     }
 
-    pub fn contains(&self, n: i32) -> bool {
-        let pos = self.find_pos(n + 1, 0);
+    pub fn contains(&self, n: T) -> bool {
+        let pos = self.find_pos(n + T::ONE, 0);
         (pos & 1) == 1
     }
 
-    pub fn min(&self) -> i32 {
-        if self.is_empty() {
-            panic!("Can't get extrema for empty IntSpan");
-        }
+    /// Panics on empty sets, see `first()` for the fallible one
+    pub fn min(&self) -> T {
+        self.first()
+            .unwrap_or_else(|| panic!("Can't get extrema for empty IntSpan"))
+    }
 
-        *self.edges.first().unwrap()
+    /// Panics on empty sets, see `last()` for the fallible one
+    pub fn max(&self) -> T {
+        self.last()
+            .unwrap_or_else(|| panic!("Can't get extrema for empty IntSpan"))
     }
 
-    pub fn max(&self) -> i32 {
-        if self.is_empty() {
-            panic!("Can't get extrema for empty IntSpan");
-        }
+    /// The smallest element, `None` for empty sets
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// assert_eq!(IntSpan::from("3-5,9").first(), Some(3));
+    /// assert_eq!(IntSpan::from("3-5,9").last(), Some(9));
+    /// assert_eq!(IntSpan::new().first(), None);
+    /// assert_eq!(IntSpan::new().last(), None);
+    /// ```
+    pub fn first(&self) -> Option<T> {
+        self.edges.first().copied()
+    }
 
-        *self.edges.last().unwrap() - 1
+    /// The largest element, `None` for empty sets
+    pub fn last(&self) -> Option<T> {
+        self.edges.last().map(|&e| e - T::ONE)
     }
 }
 
@@ -287,6 +353,21 @@ mod create {
         println!("{:?}", set.ranges());
     }
 
+    #[test]
+    #[should_panic(expected = "Out of range: 1,2147483647")]
+    fn panic_pair_range() {
+        let mut set = IntSpan::new();
+        set.add_pair(1, i32::MAX);
+        println!("{:?}", set.ranges());
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of range: -2147483648,1")]
+    fn panic_pairs_range() {
+        let set = IntSpan::from_pairs(vec![(i32::MIN, 1)]);
+        println!("{:?}", set.ranges());
+    }
+
     #[test]
     #[should_panic(expected = "Bad order: 1,-1")]
     fn panic_runlist() {
@@ -303,6 +384,117 @@ mod create {
         println!("{:?}", set.ranges());
     }
 
+    #[test]
+    fn test_try_from() {
+        // runlist, kind, byte, offset
+        let tests = vec![
+            ("abc", ParseErrorKind::InvalidDigit, b'a', 0),
+            ("1-3,5 ", ParseErrorKind::InvalidDigit, b' ', 5),
+            ("1-3;5", ParseErrorKind::InvalidDigit, b';', 3),
+            ("1-2-3", ParseErrorKind::InvalidDigit, b'-', 3),
+            ("1-3x", ParseErrorKind::InvalidDigit, b'x', 3),
+            ("2147483648", ParseErrorKind::Overflow, b'8', 9),
+            ("1,2147483646", ParseErrorKind::Overflow, b'6', 11),
+            ("1-99999999999", ParseErrorKind::Overflow, b'9', 12),
+            ("-2147483648", ParseErrorKind::Overflow, b'8', 10),
+            ("1--1", ParseErrorKind::BadOrder, b'1', 0),
+            ("1-3,9-5", ParseErrorKind::BadOrder, b'9', 4),
+            ("1,,2", ParseErrorKind::MissingNumber, b',', 2),
+            (",1", ParseErrorKind::MissingNumber, b',', 0),
+            ("1-3,,", ParseErrorKind::MissingNumber, b',', 4),
+            ("1,", ParseErrorKind::MissingNumber, b',', 1),
+            ("--5", ParseErrorKind::MissingNumber, b'-', 1),
+            ("0-", ParseErrorKind::MissingNumber, b'-', 1),
+            ("1-", ParseErrorKind::MissingNumber, b'-', 1),
+            ("1---5", ParseErrorKind::MissingNumber, b'-', 3),
+            ("-,1", ParseErrorKind::MissingNumber, b'-', 0),
+        ];
+
+        for (runlist, kind, byte, offset) in tests {
+            let err = IntSpan::try_from(runlist).err().unwrap();
+            assert_eq!(err.kind(), kind, "{}", runlist);
+            assert_eq!(err.byte(), byte, "{}", runlist);
+            assert_eq!(err.offset(), offset, "{}", runlist);
+            assert_eq!(err.runlist(), runlist);
+        }
+
+        // boundaries
+        let set = IntSpan::new();
+        let uni = format!("{}-{}", set.get_neg_inf(), set.get_pos_inf());
+        assert_eq!(IntSpan::try_from(uni.as_str()).unwrap().to_string(), uni);
+        assert_eq!("1-3,5".parse::<IntSpan>().unwrap().cardinality(), 4);
+    }
+
+    #[test]
+    fn wide_coords() {
+        let set: IntSpan64 = "-5000000000--1,1-3000000000".parse().unwrap();
+        assert_eq!(set.cardinality(), 8_000_000_000);
+        assert_eq!(set.to_string(), "-5000000000--1,1-3000000000");
+        assert_eq!(set.pad(10).to_string(), "-5000000010-3000000010");
+        assert_eq!(
+            set.trim(2_000_000_000).to_string(),
+            "-3000000000--2000000001"
+        );
+
+        let set: GenericIntSpan<u64> = "6-10,20-30".parse().unwrap();
+        assert_eq!(set.to_string(), "6-10,20-30");
+        assert_eq!(set.pad(5).to_string(), "1-35");
+        assert_eq!(set.trim(2).to_string(), "8,22-28");
+        assert!(set.is_finite());
+
+        let err = GenericIntSpan::<u64>::try_from("1,-3").err().unwrap();
+        assert_eq!(err.kind(), ParseErrorKind::Overflow);
+        assert_eq!(err.offset(), 3);
+
+        // 0 is the negative infinity of unsigned types
+        for runlist in &["0", "0-5", "3,0-1"] {
+            let err = GenericIntSpan::<u32>::try_from(*runlist).err().unwrap();
+            assert_eq!(err.kind(), ParseErrorKind::Overflow, "{}", runlist);
+            let err = GenericIntSpan::<u64>::try_from(*runlist).err().unwrap();
+            assert_eq!(err.kind(), ParseErrorKind::Overflow, "{}", runlist);
+        }
+        let set: GenericIntSpan<u32> = "1-5".parse().unwrap();
+        assert_eq!(set.complement().complement(), set);
+        assert_eq!(set.holes().to_string(), "-");
+        assert!(IntSpan::try_from("0-5").unwrap().is_finite());
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        // pairs, runlist
+        let tests = vec![
+            (vec![], "-"),
+            (vec![(1, 1)], "1"),
+            (vec![(5, 6), (1, 2), (3, 4)], "1-6"),
+            (vec![(1, 10), (2, 3), (12, 13)], "1-10,12-13"),
+            (vec![(-5, -1), (1, 3), (0, 0), (8, 9)], "-5-3,8-9"),
+            (vec![(3, 4), (3, 4), (1, 1)], "1,3-4"),
+        ];
+
+        for (pairs, runlist) in tests {
+            let mut set = IntSpan::new();
+            for &(lower, upper) in &pairs {
+                set.add_pair(lower, upper);
+            }
+            assert_eq!(set.to_string(), runlist);
+            assert_eq!(IntSpan::from_pairs(pairs), set);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Bad order: 3,1")]
+    fn panic_from_pairs() {
+        let set = IntSpan::from_pairs(vec![(1, 2), (3, 1)]);
+        println!("{:?}", set.ranges());
+    }
+
+    #[test]
+    #[should_panic(expected = "Number overflow: 9 at 12 of 1-99999999999")]
+    fn panic_runlist_overflow() {
+        let set = IntSpan::from("1-99999999999");
+        println!("{:?}", set.ranges());
+    }
+
     // Read as 1-11
     //#[test]
     //#[should_panic(expected = "Bad order: 1,-1")]
@@ -316,9 +508,9 @@ mod create {
 //----------------------------------------------------------
 // Set cardinality
 //----------------------------------------------------------
-impl IntSpan {
-    pub fn cardinality(&self) -> i32 {
-        let mut cardinality: i32 = 0;
+impl<T: Coord> GenericIntSpan<T> {
+    pub fn cardinality(&self) -> T {
+        let mut cardinality = T::ZERO;
 
         if self.is_empty() {
             return cardinality;
@@ -326,9 +518,9 @@ impl IntSpan {
 
         for i in 0..self.span_size() {
             let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
+            let upper = *self.edges.get(i * 2 + 1).unwrap() - T::ONE;
 
-            cardinality += upper - lower + 1;
+            cardinality += upper - lower + T::ONE;
         }
 
         cardinality
@@ -338,12 +530,13 @@ impl IntSpan {
         self.edges.is_empty()
     }
 
+    /// `false` for an empty set, as are `is_pos_inf()` and `is_infinite()`
     pub fn is_neg_inf(&self) -> bool {
-        *self.edges.first().unwrap() == self.neg_inf
+        self.edges.first() == Some(&T::NEG_INF)
     }
 
     pub fn is_pos_inf(&self) -> bool {
-        *self.edges.last().unwrap() == self.pos_inf
+        self.edges.last() == Some(&T::POS_INF)
     }
 
     pub fn is_infinite(&self) -> bool {
@@ -362,16 +555,15 @@ impl IntSpan {
 //----------------------------------------------------------
 // Member operations (mutate original set)
 //----------------------------------------------------------
-impl IntSpan {
-    pub fn add_pair(&mut self, mut lower: i32, mut upper: i32) {
-        if lower > upper {
-            panic!("Bad order: {},{}", lower, upper)
-        }
+impl<T: Coord> GenericIntSpan<T> {
+    pub fn add_pair(&mut self, mut lower: T, mut upper: T) {
+        Self::check_pair(lower, upper);
 
-        upper += 1;
+        self.prefix = None;
+        upper += T::ONE;
 
         let mut lower_pos = self.find_pos(lower, 0);
-        let mut upper_pos = self.find_pos(upper + 1, lower_pos);
+        let mut upper_pos = self.find_pos(upper + T::ONE, lower_pos);
 
         if lower_pos & 1 == 1 {
             lower_pos -= 1;
@@ -390,50 +582,38 @@ impl IntSpan {
         self.edges.insert(lower_pos + 1, upper);
     }
 
-    pub fn add_n(&mut self, n: i32) {
+    // Panics unless `lower..=upper` lies within the infinities
+    fn check_pair(lower: T, upper: T) {
+        if lower > upper {
+            panic!("Bad order: {},{}", lower, upper)
+        }
+        if lower < T::NEG_INF || upper > T::POS_INF - T::ONE {
+            panic!("Out of range: {},{}", lower, upper)
+        }
+    }
+
+    pub fn add_n(&mut self, n: T) {
         self.add_pair(n, n);
     }
 
-    pub fn add_ranges(&mut self, ranges: &Vec<i32>) {
-        if ranges.len() % 2 != 0 {
+    pub fn add_ranges(&mut self, ranges: &[T]) {
+        if ranges.len() & 1 == 1 {
             panic!("Number of ranges must be even")
         }
 
-        for i in 0..(ranges.len() / 2) {
-            let lower = *ranges.get(i * 2).unwrap();
-            let upper = *ranges.get(i * 2 + 1).unwrap();
-
-            self.add_pair(lower, upper);
+        let new = Self::from_pairs(ranges.chunks(2).map(|pair| (pair[0], pair[1])));
+        if self.is_empty() {
+            *self = new;
+        } else {
+            self.merge(&new);
         }
-
-        // CAUTIONS: can't capture bad orders
-        //        // When this IntSpan is empty, just convert ranges to edges
-        //        if self.is_empty() {
-        //            for i in 0..ranges.len() {
-        //                // odd index means upper
-        //                if (i & 1) == 1 {
-        //                    self.edges.push(*ranges.get(i).unwrap() + 1);
-        //                } else {
-        //                    self.edges.push(*ranges.get(i).unwrap());
-        //                }
-        //            }
-        //        } else {
-        //            for i in 0..(ranges.len() / 2) {
-        //                let lower = *ranges.get(i * 2).unwrap();
-        //                let upper = *ranges.get(i * 2 + 1).unwrap();
-        //
-        //                self.add_pair(lower, upper);
-        //            }
-        //        }
     }
 
     pub fn merge(&mut self, other: &Self) {
-        let ranges = other.ranges();
-
-        self.add_ranges(&ranges);
+        *self = self.union(other);
     }
 
-    pub fn add_vec(&mut self, ints: &Vec<i32>) {
+    pub fn add_vec(&mut self, ints: &[T]) {
         let ranges = self.list_to_ranges(ints);
 
         self.add_ranges(&ranges);
@@ -446,17 +626,18 @@ impl IntSpan {
     {
         let s = runlist.into();
         // skip empty runlist
-        if !s.is_empty() && !s.eq(&self.empty_string) {
-            let ranges = self.runlist_to_ranges(&s);
+        if !s.is_empty() && s != EMPTY_STRING {
+            let ranges = Self::runlist_to_ranges(&s).unwrap_or_else(|e| panic!("{}", e));
             self.add_ranges(&ranges);
         }
     }
 
     pub fn invert(&mut self) {
+        self.prefix = None;
         if self.is_empty() {
             // Universal set
-            self.edges.push(self.neg_inf);
-            self.edges.push(self.pos_inf);
+            self.edges.push(T::NEG_INF);
+            self.edges.push(T::POS_INF);
         } else {
             // Either add or remove infinity from each end. The net effect is always an even number
             // of additions and deletions
@@ -464,29 +645,29 @@ impl IntSpan {
             if self.is_neg_inf() {
                 self.edges.remove(0); // shift
             } else {
-                self.edges.insert(0, self.neg_inf); // unshift
+                self.edges.insert(0, T::NEG_INF); // unshift
             }
 
             if self.is_pos_inf() {
                 self.edges.pop(); // pop
             } else {
-                self.edges.push(self.pos_inf); // push
+                self.edges.push(T::POS_INF); // push
             }
         }
     }
 
-    pub fn remove_pair(&mut self, lower: i32, upper: i32) {
+    pub fn remove_pair(&mut self, lower: T, upper: T) {
         self.invert();
         self.add_pair(lower, upper);
         self.invert();
     }
 
-    pub fn remove_n(&mut self, n: i32) {
+    pub fn remove_n(&mut self, n: T) {
         self.remove_pair(n, n);
     }
 
-    pub fn remove_ranges(&mut self, ranges: &Vec<i32>) {
-        if ranges.len() % 2 != 0 {
+    pub fn remove_ranges(&mut self, ranges: &[T]) {
+        if ranges.len() & 1 == 1 {
             panic!("Number of ranges must be even");
         }
 
@@ -496,12 +677,10 @@ impl IntSpan {
     }
 
     pub fn subtract(&mut self, other: &Self) {
-        let ranges = other.ranges();
-
-        self.remove_ranges(&ranges);
+        *self = self.diff(other);
     }
 
-    pub fn remove_vec(&mut self, ints: &Vec<i32>) {
+    pub fn remove_vec(&mut self, ints: &[T]) {
         let ranges = self.list_to_ranges(ints);
 
         self.remove_ranges(&ranges);
@@ -513,8 +692,8 @@ impl IntSpan {
     {
         let s = runlist.into();
         // skip empty runlist
-        if !s.is_empty() && !s.eq(&self.empty_string) {
-            let ranges = self.runlist_to_ranges(&s);
+        if !s.is_empty() && s != EMPTY_STRING {
+            let ranges = Self::runlist_to_ranges(&s).unwrap_or_else(|e| panic!("{}", e));
             self.remove_ranges(&ranges);
         }
     }
@@ -526,23 +705,23 @@ mod mutate {
 
     #[test]
     fn test_mutate() {
-        let sets = vec!["-", "1", "1-2", "1,3-5"];
+        let sets = ["-", "1", "1-2", "1,3-5"];
 
-        let contains = vec![
+        let contains = [
             vec![false, false, false, false],
             vec![true, false, false, false],
             vec![true, true, false, false],
             vec![true, false, true, true],
         ];
 
-        let added = vec![
+        let added = [
             vec!["1", "2", "3", "4"],
             vec!["1", "1-2", "1,3", "1,4"],
             vec!["1-2", "1-2", "1-3", "1-2,4"],
             vec!["1,3-5", "1-5", "1,3-5", "1,3-5"],
         ];
 
-        let removed = vec![
+        let removed = [
             vec!["-", "-", "-", "-"],
             vec!["-", "1", "1", "1"],
             vec!["2", "1", "1-2", "1-2"],
@@ -561,19 +740,13 @@ mod mutate {
                 set_removed.remove_n(n);
 
                 // contains
-                assert_eq!(set.contains(n), contains[i as usize][j as usize]);
+                assert_eq!(set.contains(n), contains[i][j as usize]);
 
                 // added
-                assert_eq!(
-                    set_added.to_string(),
-                    added[i as usize][j as usize].to_string()
-                );
+                assert_eq!(set_added.to_string(), added[i][j as usize].to_string());
 
                 // removed
-                assert_eq!(
-                    set_removed.to_string(),
-                    removed[i as usize][j as usize].to_string()
-                );
+                assert_eq!(set_removed.to_string(), removed[i][j as usize].to_string());
             }
         }
     }
@@ -582,20 +755,14 @@ mod mutate {
 //----------------------------------------------------------
 // Set binary operations (create new set)
 //----------------------------------------------------------
-impl IntSpan {
+impl<T: Coord> GenericIntSpan<T> {
     pub fn copy(&self) -> Self {
-        IntSpan {
-            edges: self.edges.clone(),
-            pos_inf: 2_147_483_647 - 1, // INT_MAX - 1, Real Largest int is POS_INF - 1
-            neg_inf: -2_147_483_648 + 1, // INT_MIN + 1
-            empty_string: "-".to_string(),
-        }
+        self.clone()
     }
 
+    /// Runs in O(n + m) of the numbers of spans, as do `intersect()`, `diff()` and `xor()`
     pub fn union(&self, other: &Self) -> Self {
-        let mut new = self.copy();
-        new.merge(&other);
-        new
+        self.sweep(other, |a, b| a || b)
     }
 
     pub fn complement(&self) -> Self {
@@ -605,31 +772,53 @@ impl IntSpan {
     }
 
     pub fn diff(&self, other: &Self) -> Self {
-        if self.is_empty() {
-            Self::new()
-        } else {
-            let mut new = self.copy();
-            new.subtract(&other);
-            new
-        }
+        self.sweep(other, |a, b| a && !b)
     }
 
     pub fn intersect(&self, other: &Self) -> Self {
-        if self.is_empty() || other.is_empty() {
-            Self::new()
-        } else {
-            let mut new = self.complement();
-            new.merge(&other.complement());
-            new.invert();
-            new
-        }
+        self.sweep(other, |a, b| a && b)
     }
 
     pub fn xor(&self, other: &Self) -> Self {
-        let mut new = self.union(&other);
-        let intersect = self.intersect(&other);
-        new.subtract(&intersect);
-        new
+        self.sweep(other, |a, b| a != b)
+    }
+
+    // Walks both sorted edge lists once, keeping elements for which `op(in self, in other)` holds.
+    // `op(false, false)` must be false.
+    fn sweep<F: Fn(bool, bool) -> bool>(&self, other: &Self, op: F) -> Self {
+        let (a, b) = (&self.edges, &other.edges);
+        let mut edges: Vec<T> = Vec::with_capacity(a.len() + b.len());
+
+        let (mut i, mut j) = (0, 0);
+        let mut inside = false;
+        while i < a.len() || j < b.len() {
+            let x = match (a.get(i), b.get(j)) {
+                (Some(&p), Some(&q)) => p.min(q),
+                (Some(&p), None) => p,
+                (None, Some(&q)) => q,
+                (None, None) => unreachable!(),
+            };
+
+            // edges of one set are strictly increasing, so each advances at most once
+            if a.get(i) == Some(&x) {
+                i += 1;
+            }
+            if b.get(j) == Some(&x) {
+                j += 1;
+            }
+
+            // having passed an odd number of edges means being inside a span
+            let now = op((i & 1) == 1, (j & 1) == 1);
+            if now != inside {
+                edges.push(x);
+                inside = now;
+            }
+        }
+
+        Self {
+            edges,
+            prefix: None,
+        }
     }
 }
 
@@ -680,35 +869,273 @@ mod binary {
             assert_eq!(ib.diff(&ia).to_string(), ba);
         }
     }
+
+    #[test]
+    fn sweep_against_elements() {
+        // a tiny LCG keeps the sets reproducible
+        let mut seed: u32 = 42;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 64
+        };
+
+        for _ in 0..200 {
+            let ints_a: Vec<i32> = (0..20).map(|_| next() as i32).collect();
+            let ints_b: Vec<i32> = (0..20).map(|_| next() as i32).collect();
+            let mut ia = IntSpan::new();
+            ia.add_vec(&ints_a);
+            let mut ib = IntSpan::new();
+            ib.add_vec(&ints_b);
+
+            let expect = |op: fn(bool, bool) -> bool| -> Vec<i32> {
+                (0..64)
+                    .filter(|&n| op(ia.contains(n), ib.contains(n)))
+                    .collect()
+            };
+            assert_eq!(ia.union(&ib).to_vec(), expect(|a, b| a || b));
+            assert_eq!(ia.intersect(&ib).to_vec(), expect(|a, b| a && b));
+            assert_eq!(ia.diff(&ib).to_vec(), expect(|a, b| a && !b));
+            assert_eq!(ia.xor(&ib).to_vec(), expect(|a, b| a != b));
+        }
+
+        // infinite sets
+        let uni = IntSpan::new().complement();
+        let set = IntSpan::from("1-10");
+        assert_eq!(uni.intersect(&set), set);
+        assert_eq!(uni.union(&set), uni);
+        assert_eq!(uni.diff(&set), set.complement());
+        assert_eq!(set.xor(&uni), set.complement());
+    }
 }
 
 //----------------------------------------------------------
-// Set relations
+// N-way operations (create new sets)
 //----------------------------------------------------------
-impl IntSpan {
-    pub fn equals(&self, other: &Self) -> bool {
-        let edges = &self.edges;
-        let edges_other = &other.edges;
+impl<T: Coord> GenericIntSpan<T> {
+    /// Elements in any of the sets
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let sets = vec![IntSpan::from("1-10"), IntSpan::from("5-15"), IntSpan::from("8-20")];
+    /// let refs: Vec<&IntSpan> = sets.iter().collect();
+    /// assert_eq!(IntSpan::union_all(&refs).to_string(), "1-20");
+    /// assert_eq!(IntSpan::intersect_all(&refs).to_string(), "8-10");
+    /// assert_eq!(IntSpan::at_least(&refs, 2).to_string(), "5-15");
+    /// ```
+    pub fn union_all(sets: &[&Self]) -> Self {
+        Self::at_least(sets, 1)
+    }
+
+    /// Elements in every one of the sets, empty for no sets
+    pub fn intersect_all(sets: &[&Self]) -> Self {
+        if sets.is_empty() {
+            return Self::new();
+        }
+        Self::at_least(sets, sets.len())
+    }
+
+    /// Elements in `k` or more of the sets. `k` of 0 is treated as 1
+    pub fn at_least(sets: &[&Self], k: usize) -> Self {
+        let k = k.max(1);
+        let mut edges: Vec<T> = vec![];
+        for (lower, upper, members) in Self::segments_n(sets) {
+            if members.iter().filter(|&&m| m).count() < k {
+                continue;
+            }
+            match edges.last_mut() {
+                Some(last) if *last == lower => *last = upper,
+                _ => {
+                    edges.push(lower);
+                    edges.push(upper);
+                }
+            }
+        }
+
+        Self {
+            edges,
+            prefix: None,
+        }
+    }
+
+    /// For each set, the elements in no other set
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let sets = vec![IntSpan::from("1-10"), IntSpan::from("5-15"), IntSpan::from("8-20")];
+    /// let refs: Vec<&IntSpan> = sets.iter().collect();
+    /// let exclusive: Vec<String> = IntSpan::exclusive(&refs).iter().map(|s| s.to_string()).collect();
+    /// assert_eq!(exclusive, vec!["1-4", "-", "16-20"]);
+    /// ```
+    pub fn exclusive(sets: &[&Self]) -> Vec<Self> {
+        let mut edges_of: Vec<Vec<T>> = vec![vec![]; sets.len()];
+        for (lower, upper, members) in Self::segments_n(sets) {
+            let mut idx = members
+                .iter()
+                .enumerate()
+                .filter(|(_, &m)| m)
+                .map(|(i, _)| i);
+            if let (Some(i), None) = (idx.next(), idx.next()) {
+                edges_of[i].push(lower);
+                edges_of[i].push(upper);
+            }
+        }
+
+        // segments with the same members were merged, so these edges never touch
+        edges_of
+            .into_iter()
+            .map(|edges| Self {
+                edges,
+                prefix: None,
+            })
+            .collect()
+    }
+
+    /// Splits the union of the sets into `(lower, upper, members)` segments, where `members`
+    /// flags the sets containing every element of the segment. Adjacent segments always differ
+    /// in their members
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let sets = vec![IntSpan::from("1-10"), IntSpan::from("5-15")];
+    /// let refs: Vec<&IntSpan> = sets.iter().collect();
+    /// assert_eq!(
+    ///     IntSpan::membership(&refs),
+    ///     vec![
+    ///         (1, 4, vec![true, false]),
+    ///         (5, 10, vec![true, true]),
+    ///         (11, 15, vec![false, true]),
+    ///     ]
+    /// );
+    /// ```
+    pub fn membership(sets: &[&Self]) -> Vec<(T, T, Vec<bool>)> {
+        Self::segments_n(sets)
+            .into_iter()
+            .map(|(lower, upper, members)| (lower, upper - T::ONE, members))
+            .collect()
+    }
+
+    // Half-open segments of the union, from one sweep over the edges of all sets
+    fn segments_n(sets: &[&Self]) -> Vec<(T, T, Vec<bool>)> {
+        let mut events: Vec<(T, usize)> = sets
+            .iter()
+            .enumerate()
+            .flat_map(|(i, set)| set.edges.iter().map(move |&e| (e, i)))
+            .collect();
+        events.sort_unstable();
+
+        let mut segments: Vec<(T, T, Vec<bool>)> = vec![];
+        let mut members = vec![false; sets.len()];
+        let mut inside = 0;
+        let mut i = 0;
+        while i < events.len() {
+            let x = events[i].0;
+            // each edge toggles the membership of its set
+            while i < events.len() && events[i].0 == x {
+                let m = &mut members[events[i].1];
+                *m = !*m;
+                if *m {
+                    inside += 1;
+                } else {
+                    inside -= 1;
+                }
+                i += 1;
+            }
 
-        if edges.len() != edges_other.len() {
-            return false;
+            if inside == 0 || i == events.len() {
+                continue;
+            }
+            // a set toggles at most once per position, so neighbours never share members
+            segments.push((x, events[i].0, members.clone()));
         }
 
-        for i in 0..edges.len() {
-            if edges.get(i) != edges_other.get(i) {
-                return false;
+        segments
+    }
+}
+
+#[cfg(test)]
+mod nway {
+    use super::*;
+
+    #[test]
+    fn nway_against_elements() {
+        // a tiny LCG keeps the sets reproducible
+        let mut seed: u32 = 7;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 64
+        };
+
+        for n in 0..5 {
+            let sets: Vec<IntSpan> = (0..n)
+                .map(|_| {
+                    let ints: Vec<i32> = (0..20).map(|_| next() as i32).collect();
+                    let mut set = IntSpan::new();
+                    set.add_vec(&ints);
+                    set
+                })
+                .collect();
+            let refs: Vec<&IntSpan> = sets.iter().collect();
+
+            let count = |x: i32| sets.iter().filter(|s| s.contains(x)).count();
+            let expect =
+                |f: &dyn Fn(i32) -> bool| -> Vec<i32> { (0..64).filter(|&x| f(x)).collect() };
+
+            assert_eq!(
+                IntSpan::union_all(&refs).to_vec(),
+                expect(&|x| count(x) >= 1)
+            );
+            assert_eq!(
+                IntSpan::intersect_all(&refs).to_vec(),
+                expect(&|x| n > 0 && count(x) == n)
+            );
+            for k in 1..=n {
+                assert_eq!(
+                    IntSpan::at_least(&refs, k).to_vec(),
+                    expect(&|x| count(x) >= k)
+                );
+            }
+            for (i, exclusive) in IntSpan::exclusive(&refs).iter().enumerate() {
+                assert_eq!(
+                    exclusive.to_vec(),
+                    expect(&|x| sets[i].contains(x) && count(x) == 1)
+                );
+            }
+
+            let mut covered = 0;
+            for (lower, upper, members) in IntSpan::membership(&refs) {
+                for x in lower..=upper {
+                    for (i, set) in sets.iter().enumerate() {
+                        assert_eq!(set.contains(x), members[i]);
+                    }
+                }
+                covered += upper - lower + 1;
             }
+            assert_eq!(covered, IntSpan::union_all(&refs).cardinality());
         }
 
-        true
+        // infinite sets
+        let uni = IntSpan::new().complement();
+        let set = IntSpan::from("1-10");
+        assert_eq!(IntSpan::intersect_all(&[&uni, &set]), set);
+        assert_eq!(IntSpan::union_all(&[&uni, &set]), uni);
+        assert_eq!(IntSpan::exclusive(&[&uni, &set])[0], set.complement());
+    }
+}
+
+//----------------------------------------------------------
+// Set relations
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    pub fn equals(&self, other: &Self) -> bool {
+        self == other
     }
 
     pub fn subset(&self, other: &Self) -> bool {
-        self.diff(&other).is_empty()
+        self.diff(other).is_empty()
     }
 
     pub fn superset(&self, other: &Self) -> bool {
-        other.diff(&self).is_empty()
+        other.diff(self).is_empty()
     }
 }
 
@@ -718,9 +1145,9 @@ mod relation {
 
     #[test]
     fn test_relation() {
-        let sets = vec!["-", "1", "5", "1-5", "3-7", "1-3,8,10-23"];
+        let sets = ["-", "1", "5", "1-5", "3-7", "1-3,8,10-23"];
 
-        let equals = vec![
+        let equals = [
             vec![1, 0, 0, 0, 0, 0],
             vec![0, 1, 0, 0, 0, 0],
             vec![0, 0, 1, 0, 0, 0],
@@ -729,7 +1156,7 @@ mod relation {
             vec![0, 0, 0, 0, 0, 1],
         ];
 
-        let subset = vec![
+        let subset = [
             vec![1, 1, 1, 1, 1, 1],
             vec![0, 1, 0, 1, 0, 1],
             vec![0, 0, 1, 1, 1, 0],
@@ -738,7 +1165,7 @@ mod relation {
             vec![0, 0, 0, 0, 0, 1],
         ];
 
-        let superset = vec![
+        let superset = [
             vec![1, 0, 0, 0, 0, 0],
             vec![1, 1, 0, 0, 0, 0],
             vec![1, 0, 1, 0, 0, 0],
@@ -753,160 +1180,586 @@ mod relation {
                 let b = IntSpan::from(sets[j]);
 
                 // equals
-                assert_eq!(a.equals(&b), equals[i as usize][j as usize] != 0);
+                assert_eq!(a.equals(&b), equals[i][j] != 0);
 
                 // subset
-                assert_eq!(a.subset(&b), subset[i as usize][j as usize] != 0);
+                assert_eq!(a.subset(&b), subset[i][j] != 0);
 
                 // superset
-                assert_eq!(a.superset(&b), superset[i as usize][j as usize] != 0);
+                assert_eq!(a.superset(&b), superset[i][j] != 0);
+
+                // std traits agree with the methods
+                assert_eq!(a == b, equals[i][j] != 0);
+                assert_eq!(a <= b, subset[i][j] != 0);
+                assert_eq!(a >= b, superset[i][j] != 0);
             }
         }
     }
 }
 
 //----------------------------------------------------------
-// Indexing
+// Operators
 //----------------------------------------------------------
-impl IntSpan {
-    fn at_pos(&self, index: i32) -> i32 {
-        let mut element = self.min();
-        let mut ele_before = 0;
-
-        for i in 0..self.span_size() {
-            let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
+macro_rules! impl_set_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $set_method:ident) => {
+        impl<T: Coord> $op<&GenericIntSpan<T>> for &GenericIntSpan<T> {
+            type Output = GenericIntSpan<T>;
 
-            let span_len = upper - lower + 1;
-
-            if index > ele_before + span_len {
-                ele_before += span_len;
-            } else {
-                element = index - ele_before - 1 + lower;
-                break;
+            fn $method(self, other: &GenericIntSpan<T>) -> Self::Output {
+                self.$set_method(other)
             }
         }
 
-        element
-    }
-
-    fn at_neg(&self, index: i32) -> i32 {
-        let mut element = self.max();
-        let mut ele_after = 0;
+        impl<T: Coord> $op for GenericIntSpan<T> {
+            type Output = Self;
 
-        for i in (0..self.span_size()).rev() {
-            let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
+            fn $method(self, other: Self) -> Self::Output {
+                self.$set_method(&other)
+            }
+        }
 
-            let span_len = upper - lower + 1;
+        impl<T: Coord> $op_assign<&GenericIntSpan<T>> for GenericIntSpan<T> {
+            fn $method_assign(&mut self, other: &Self) {
+                *self = self.$set_method(other);
+            }
+        }
 
-            if index > ele_after + span_len {
-                ele_after += span_len;
-            } else {
-                element = upper - (index - ele_after) + 1;
-                break;
+        impl<T: Coord> $op_assign for GenericIntSpan<T> {
+            fn $method_assign(&mut self, other: Self) {
+                *self = self.$set_method(&other);
             }
         }
+    };
+}
 
-        element
+// `|` union, `&` intersect, `-` diff, `^` xor
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+impl_set_op!(BitAnd, bitand, BitAndAssign, bitand_assign, intersect);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, diff);
+impl_set_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+
+/// `!` complement
+///
+/// ```
+/// # use intspan::IntSpan;
+/// let a = IntSpan::from("1-5");
+/// let b = IntSpan::from("3-7");
+/// assert_eq!((&a | &b).to_string(), "1-7");
+/// assert_eq!((&a & &b).to_string(), "3-5");
+/// assert_eq!((&a - &b).to_string(), "1-2");
+/// assert_eq!((&a ^ &b).to_string(), "1-2,6-7");
+/// assert_eq!(!!&a, a);
+///
+/// let mut c = a.clone();
+/// c |= &b;
+/// c -= IntSpan::from("4");
+/// assert_eq!(c.to_string(), "1-3,5-7");
+/// ```
+impl<T: Coord> Not for &GenericIntSpan<T> {
+    type Output = GenericIntSpan<T>;
+
+    fn not(self) -> Self::Output {
+        self.complement()
     }
+}
 
-    pub fn at(&self, index: i32) -> i32 {
-        if self.is_empty() {
-            panic!("Indexing on an empty set");
-        }
-        if i32::abs(index) < 1 {
-            panic!("Index can't be 0");
-        }
-        if i32::abs(index) > self.cardinality() {
-            panic!("Out of max index");
-        }
+impl<T: Coord> Not for GenericIntSpan<T> {
+    type Output = Self;
 
-        if index > 0 {
-            self.at_pos(index)
-        } else {
-            self.at_neg(-index)
-        }
+    fn not(self) -> Self::Output {
+        self.complement()
     }
+}
 
-    pub fn index(&self, element: i32) -> i32 {
-        if self.is_empty() {
-            panic!("Indexing on an empty set");
-        }
-        if !self.contains(element) {
-            panic!("Element doesn't exist");
-        }
+impl<T: Coord> PartialEq for GenericIntSpan<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.edges == other.edges
+    }
+}
 
-        let mut index = -1; // not valid
-        let mut ele_before = 0;
+impl<T: Coord> Eq for GenericIntSpan<T> {}
 
-        for i in 0..self.span_size() {
-            let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
-            let span_len = upper - lower + 1;
+impl<T: Coord> Hash for GenericIntSpan<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.edges.hash(state);
+    }
+}
 
-            if element >= lower && element <= upper {
-                index = element - lower + 1 + ele_before;
-            } else {
-                ele_before += span_len;
-            }
+/// Sets are ordered by inclusion, disjoint or overlapping sets are not comparable
+///
+/// ```
+/// # use intspan::IntSpan;
+/// use std::cmp::Ordering;
+///
+/// let a = IntSpan::from("1-5");
+/// assert!(IntSpan::from("2-3") < a);
+/// assert!(a >= IntSpan::from("1-5"));
+/// assert_eq!(a.partial_cmp(&IntSpan::from("4-9")), None);
+/// assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+/// ```
+impl<T: Coord> PartialOrd for GenericIntSpan<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.subset(other) {
+            Some(Ordering::Less)
+        } else if self.superset(other) {
+            Some(Ordering::Greater)
+        } else {
+            None
         }
+    }
+}
 
-        index
+/// Shows the runlist
+///
+/// ```
+/// # use intspan::IntSpan;
+/// let set = IntSpan::from("1-3,5");
+/// assert_eq!(format!("{:?}", set), "IntSpan(1-3,5)");
+/// ```
+impl<T: Coord> fmt::Debug for GenericIntSpan<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IntSpan({})", self)
     }
 }
 
 #[cfg(test)]
-mod index {
+mod operator {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
-    fn test_index() {
-        // runlist, n, exp_index, exp_element
+    fn test_operator() {
+        // A B U I X A-B B-A
         let tests = vec![
-            // None
-            ("-", 1, None, None),
-            ("-", -1, None, None),
-            ("1-10,21-30", 25, None, Some(15)),
-            ("1-10,21-30", -25, None, None),
-            // at_pos
-            ("0-9", 1, Some(0), Some(2)),
-            ("0-9", 6, Some(5), Some(7)),
-            ("0-9", 10, Some(9), None),
-            ("0-9", 11, None, None),
-            // at_neg
-            ("0-9", -1, Some(9), None),
-            ("0-9", -5, Some(5), None),
-            ("0-9", -10, Some(0), None),
-            ("0-9", -11, None, None),
-            // at_pos
-            ("1-10,21-30,41-50", 6, Some(6), Some(6)),
-            ("1-10,21-30,41-50", 16, Some(26), None),
-            ("1-10,21-30,41-50", 26, Some(46), Some(16)),
-            ("1-10,21-30,41-50", 31, None, None),
-            // at_neg
-            ("1-10,21-30,41-50", -1, Some(50), None),
-            ("1-10,21-30,41-50", -11, Some(30), None),
-            ("1-10,21-30,41-50", -21, Some(10), None),
-            ("1-10,21-30,41-50", -30, Some(1), None),
-            ("1-10,21-30,41-50", -31, None, None),
-        ];
-
-        for (runlist, n, exp_index, exp_element) in tests {
-            let set = IntSpan::from(runlist);
-
-            // at
-            if exp_index.is_some() {
-                assert_eq!(set.at(n), exp_index.unwrap());
-            }
+            ("1", "2", "1-2", "-", "1-2", "1", "2"),
+            ("3-9", "4-8", "3-9", "4-8", "3,9", "3,9", "-"),
+            (
+                "1-3,5,8-11",
+                "1-6",
+                "1-6,8-11",
+                "1-3,5",
+                "4,6,8-11",
+                "8-11",
+                "4,6",
+            ),
+        ];
+
+        for (a, b, u, i, x, ab, ba) in tests {
+            let ia = IntSpan::from(a);
+            let ib = IntSpan::from(b);
+
+            assert_eq!((&ia | &ib).to_string(), u);
+            assert_eq!((&ia & &ib).to_string(), i);
+            assert_eq!((&ia ^ &ib).to_string(), x);
+            assert_eq!((&ia - &ib).to_string(), ab);
+            assert_eq!((ib.clone() - ia.clone()).to_string(), ba);
+
+            let mut c = ia.clone();
+            c &= &ib;
+            assert_eq!(c.to_string(), i);
+            c ^= ib.clone();
+            assert_eq!(c.to_string(), ba);
+            c |= ia.clone();
+            assert_eq!(c.to_string(), u);
+
+            assert_eq!(!(!ia.clone()), ia);
+        }
+    }
+
+    #[test]
+    fn as_keys() {
+        let mut count_of: HashMap<IntSpan, i32> = HashMap::new();
+        for runlist in &["1-3", "1,2,3", "1-2", "1-2,3"] {
+            *count_of.entry(IntSpan::from(*runlist)).or_insert(0) += 1;
+        }
+
+        assert_eq!(count_of.len(), 2);
+        assert_eq!(*count_of.get(&IntSpan::from("1-3")).unwrap(), 3);
+    }
+}
+
+//----------------------------------------------------------
+// Indexing
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// Caches cumulative span lengths, so that `at()`, `index()`, `rank()` and `select()` run in
+    /// O(log n) of the number of spans instead of O(n). Any mutation drops the cache
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let mut set = IntSpan::from("1-3,11-13");
+    /// set.build_index();
+    /// assert!(set.is_indexed());
+    /// assert_eq!(set.at(4), 11);
+    ///
+    /// set.add_n(5);
+    /// assert!(!set.is_indexed());
+    /// assert_eq!(set.at(4), 5);
+    /// ```
+    pub fn build_index(&mut self) {
+        if self.prefix.is_none() {
+            self.prefix = Some(self.compute_prefix());
+        }
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.prefix.is_some()
+    }
+
+    // Cumulative span lengths, `prefix[i]` elements come before the i-th span
+    fn compute_prefix(&self) -> Vec<T> {
+        let mut prefix = Vec::with_capacity(self.span_size() + 1);
+        let mut sum = T::ZERO;
+        prefix.push(sum);
+        for (lower, upper) in self.spans() {
+            sum += upper - lower + T::ONE;
+            prefix.push(sum);
+        }
+        prefix
+    }
+
+    fn prefix(&self) -> Cow<'_, [T]> {
+        match &self.prefix {
+            Some(prefix) => Cow::Borrowed(prefix),
+            None => Cow::Owned(self.compute_prefix()),
+        }
+    }
+
+    fn cached_cardinality(&self) -> T {
+        match &self.prefix {
+            Some(prefix) => *prefix.last().unwrap(),
+            None => self.cardinality(),
+        }
+    }
+
+    // Index of the last span whose lower bound is not larger than `n`, if any
+    fn span_at_or_before(&self, n: T) -> Option<usize> {
+        // edges are sorted, the ones not larger than n cover spans starting at or before it
+        let count = self.edges.partition_point(|&e| e <= n).div_ceil(2);
+        count.checked_sub(1)
+    }
+
+    fn at_pos(&self, index: T) -> T {
+        let prefix = self.prefix();
+        // the first span whose cumulative length reaches index
+        let i = prefix[1..].partition_point(|&p| p < index);
+
+        self.edges[i * 2] + (index - prefix[i] - T::ONE)
+    }
+
+    fn at_neg(&self, index: T) -> T {
+        self.at_pos(self.cached_cardinality() - index + T::ONE)
+    }
+
+    /// Returns the element at `index`, counting from 1. Negative indices count from the end
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.at(4), 11);
+    /// assert_eq!(set.at(-1), 13);
+    /// ```
+    pub fn at(&self, index: T) -> T {
+        if self.is_empty() {
+            panic!("Indexing on an empty set");
+        }
+        // negative indices count from the end, they never show up with unsigned coordinates.
+        // T::MIN has no positive counterpart, it's out of any range
+        let abs = if index < T::ZERO {
+            index
+                .checked_neg()
+                .unwrap_or_else(|| panic!("Out of max index"))
+        } else {
+            index
+        };
+        if abs < T::ONE {
+            panic!("Index can't be 0");
+        }
+        if abs > self.cached_cardinality() {
+            panic!("Out of max index");
+        }
+
+        if index > T::ZERO {
+            self.at_pos(index)
+        } else {
+            self.at_neg(abs)
+        }
+    }
+
+    /// Fallible version of `at()`, `None` for empty sets, index 0 or indices out of range
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.get(4), Some(11));
+    /// assert_eq!(set.get(-6), Some(1));
+    /// assert_eq!(set.get(0), None);
+    /// assert_eq!(set.get(7), None);
+    /// assert_eq!(set.get(-7), None);
+    /// assert_eq!(set.get(i32::MIN), None);
+    /// assert_eq!(IntSpan::new().get(1), None);
+    /// ```
+    pub fn get(&self, index: T) -> Option<T> {
+        if index < T::ZERO {
+            let abs = index.checked_neg()?;
+            if abs > self.cached_cardinality() {
+                None
+            } else {
+                Some(self.at_neg(abs))
+            }
+        } else {
+            self.select(index)
+        }
+    }
+
+    /// Fallible version of `index()`, `None` when `element` isn't in the set
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.position(12), Some(5));
+    /// assert_eq!(set.position(5), None);
+    /// assert_eq!(IntSpan::new().position(1), None);
+    /// ```
+    pub fn position(&self, element: T) -> Option<T> {
+        if self.contains(element) {
+            Some(self.rank(element))
+        } else {
+            None
+        }
+    }
+
+    /// The span containing `n` as `(lower, upper)`, `None` when `n` isn't in the set
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.find_span(12), Some((11, 13)));
+    /// assert_eq!(set.find_span(3), Some((1, 3)));
+    /// assert_eq!(set.find_span(5), None);
+    /// assert_eq!(set.find_span(0), None);
+    /// ```
+    pub fn find_span(&self, n: T) -> Option<(T, T)> {
+        let i = self.span_at_or_before(n)?;
+        let lower = self.edges[i * 2];
+        let upper = self.edges[i * 2 + 1] - T::ONE;
+
+        if n <= upper {
+            Some((lower, upper))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the 1-based index of `element`, the reverse of `at()`
+    pub fn index(&self, element: T) -> T {
+        if self.is_empty() {
+            panic!("Indexing on an empty set");
+        }
+        if !self.contains(element) {
+            panic!("Element doesn't exist");
+        }
+
+        self.rank(element)
+    }
+
+    /// Number of elements not larger than `n`. `n` needn't be in the set
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.rank(0), 0);
+    /// assert_eq!(set.rank(2), 2);
+    /// assert_eq!(set.rank(5), 3);
+    /// assert_eq!(set.rank(20), 6);
+    /// ```
+    pub fn rank(&self, n: T) -> T {
+        match self.span_at_or_before(n) {
+            None => T::ZERO,
+            Some(i) => {
+                let lower = self.edges[i * 2];
+                let upper = self.edges[i * 2 + 1] - T::ONE;
+                self.prefix()[i] + n.min(upper) - lower + T::ONE
+            }
+        }
+    }
+
+    /// The `k`-th smallest element, counting from 1, so that `select(rank(n)) == Some(n)` for members
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,11-13");
+    /// assert_eq!(set.select(4), Some(11));
+    /// assert_eq!(set.select(0), None);
+    /// assert_eq!(set.select(7), None);
+    /// ```
+    pub fn select(&self, k: T) -> Option<T> {
+        if k < T::ONE || k > self.cached_cardinality() {
+            None
+        } else {
+            Some(self.at_pos(k))
+        }
+    }
+}
+
+#[cfg(test)]
+mod index {
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        // runlist, n, exp_index, exp_element
+        let tests = vec![
+            // None
+            ("-", 1, None, None),
+            ("-", -1, None, None),
+            ("1-10,21-30", 25, None, Some(15)),
+            ("1-10,21-30", -25, None, None),
+            // at_pos
+            ("0-9", 1, Some(0), Some(2)),
+            ("0-9", 6, Some(5), Some(7)),
+            ("0-9", 10, Some(9), None),
+            ("0-9", 11, None, None),
+            // at_neg
+            ("0-9", -1, Some(9), None),
+            ("0-9", -5, Some(5), None),
+            ("0-9", -10, Some(0), None),
+            ("0-9", -11, None, None),
+            // at_pos
+            ("1-10,21-30,41-50", 6, Some(6), Some(6)),
+            ("1-10,21-30,41-50", 16, Some(26), None),
+            ("1-10,21-30,41-50", 26, Some(46), Some(16)),
+            ("1-10,21-30,41-50", 31, None, None),
+            // at_neg
+            ("1-10,21-30,41-50", -1, Some(50), None),
+            ("1-10,21-30,41-50", -11, Some(30), None),
+            ("1-10,21-30,41-50", -21, Some(10), None),
+            ("1-10,21-30,41-50", -30, Some(1), None),
+            ("1-10,21-30,41-50", -31, None, None),
+        ];
+
+        for (runlist, n, exp_index, exp_element) in tests {
+            let mut set = IntSpan::from(runlist);
+
+            // without and with the cached index
+            for _ in 0..2 {
+                // at
+                if let Some(exp) = exp_index {
+                    assert_eq!(set.at(n), exp);
+                }
+
+                // index
+                if let Some(exp) = exp_element {
+                    assert_eq!(set.index(n), exp);
+                }
+
+                set.build_index();
+            }
+        }
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let set = IntSpan::from("1-10,21-30,41-50");
+
+        // n, rank
+        let tests = vec![
+            (-5, 0),
+            (0, 0),
+            (1, 1),
+            (10, 10),
+            (15, 10),
+            (21, 11),
+            (45, 25),
+            (99, 30),
+        ];
+        for (n, exp) in tests {
+            assert_eq!(set.rank(n), exp, "{}", n);
+        }
+
+        for n in set.iter() {
+            assert_eq!(set.select(set.rank(n)), Some(n));
+        }
+        assert_eq!(set.select(0), None);
+        assert_eq!(set.select(31), None);
+        assert_eq!(IntSpan::new().rank(1), 0);
+        assert_eq!(IntSpan::new().select(1), None);
+    }
+
+    #[test]
+    fn fallible() {
+        // runlist, n, get, position, find_span
+        let tests = vec![
+            ("-", 1, None, None, None),
+            ("-", -1, None, None, None),
+            ("-", 0, None, None, None),
+            ("0-9", 0, None, Some(1), Some((0, 9))),
+            ("0-9", 1, Some(0), Some(2), Some((0, 9))),
+            ("0-9", -10, Some(0), None, None),
+            ("0-9", 11, None, None, None),
+            ("1-10,21-30", 15, Some(25), None, None),
+            ("1-10,21-30", 25, None, Some(15), Some((21, 30))),
+            ("1-10,21-30", -11, Some(10), None, None),
+            ("1-10,21-30", -21, None, None, None),
+            ("1-10,21-30", i32::MIN, None, None, None),
+            ("-", i32::MIN, None, None, None),
+        ];
 
-            // index
-            if exp_element.is_some() {
-                assert_eq!(set.index(n), exp_element.unwrap());
+        for (runlist, n, exp_get, exp_position, exp_span) in tests {
+            let mut set = IntSpan::from(runlist);
+
+            for _ in 0..2 {
+                assert_eq!(set.get(n), exp_get, "{} {}", runlist, n);
+                assert_eq!(set.position(n), exp_position, "{} {}", runlist, n);
+                assert_eq!(set.find_span(n), exp_span, "{} {}", runlist, n);
+
+                // agree with the panicking ones
+                if let Some(element) = exp_get {
+                    assert_eq!(set.at(n), element);
+                }
+                if let Some(index) = exp_position {
+                    assert_eq!(set.index(n), index);
+                }
+
+                set.build_index();
             }
         }
     }
 
+    #[test]
+    fn cache_invalidation() {
+        let mut set = IntSpan::from("1-10");
+        set.build_index();
+        assert_eq!(set.at(-1), 10);
+
+        set.add_pair(21, 30);
+        assert!(!set.is_indexed());
+        set.build_index();
+        assert_eq!(set.at(-1), 30);
+        assert_eq!(set.index(21), 11);
+
+        set.remove_pair(1, 5);
+        assert!(!set.is_indexed());
+        set.build_index();
+        assert_eq!(set.at(1), 6);
+
+        set.invert();
+        assert!(!set.is_indexed());
+        set.clear();
+        set.build_index();
+        assert_eq!(set.select(1), None);
+
+        // clones carry their own cache
+        let mut set = IntSpan::from("1-10");
+        set.build_index();
+        let mut other = set.clone();
+        other.add_n(0);
+        assert_eq!(other.at(1), 0);
+        assert_eq!(set.at(1), 1);
+    }
+
     #[test]
     #[should_panic(expected = "Indexing on an empty set")]
     fn panic_at_1() {
@@ -931,6 +1784,14 @@ mod index {
         println!("{:?}", set.ranges());
     }
 
+    #[test]
+    #[should_panic(expected = "Out of max index")]
+    fn panic_at_4() {
+        let set = IntSpan::from("0-9");
+        set.at(i32::MIN);
+        println!("{:?}", set.ranges());
+    }
+
     #[test]
     #[should_panic(expected = "Indexing on an empty set")]
     fn panic_index_1() {
@@ -951,9 +1812,9 @@ mod index {
 //----------------------------------------------------------
 // Spans Ops
 //----------------------------------------------------------
-impl IntSpan {
+impl<T: Coord> GenericIntSpan<T> {
     pub fn cover(&self) -> Self {
-        let mut new = IntSpan::new();
+        let mut new = Self::new();
         if !self.is_empty() {
             new.add_pair(self.min(), self.max());
         }
@@ -961,7 +1822,7 @@ impl IntSpan {
     }
 
     pub fn holes(&self) -> Self {
-        let mut new = IntSpan::new();
+        let mut new = Self::new();
         if self.is_empty() || self.is_universal() {
             // empty and universal set have no holes
             return new;
@@ -984,44 +1845,91 @@ impl IntSpan {
         new
     }
 
-    pub fn inset(&self, n: i32) -> Self {
-        let mut new = IntSpan::new();
-
-        for i in 0..self.span_size() {
-            let mut lower = *self.edges.get(i * 2).unwrap();
-            let mut upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
-
-            if lower != self.get_neg_inf() {
-                lower += n;
-            }
-            if upper != self.get_pos_inf() {
-                upper -= n;
-            }
-
-            if lower <= upper {
-                new.add_pair(lower, upper);
-            }
+    /// Trims each span by `n`, a negative `n` pads instead
+    pub fn inset(&self, n: T) -> Self {
+        if n < T::ZERO {
+            self.pad(T::ZERO - n)
+        } else {
+            self.trim(n)
         }
-
-        new
-    }
-
-    pub fn trim(&self, n: i32) -> Self {
-        self.inset(n)
     }
 
-    pub fn pad(&self, n: i32) -> Self {
-        self.inset(-n)
+    pub fn trim(&self, n: T) -> Self {
+        self.resize_spans(n, n, false)
+    }
+
+    pub fn pad(&self, n: T) -> Self {
+        self.resize_spans(n, n, true)
+    }
+
+    /// Removes `left` integers from the lower end and `right` from the upper end of each span
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10,21-30");
+    /// assert_eq!(set.trim_sides(2, 5).to_string(), "3-5,23-25");
+    /// ```
+    pub fn trim_sides(&self, left: T, right: T) -> Self {
+        self.resize_spans(left, right, false)
+    }
+
+    /// Adds `left` integers below and `right` above each span
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("11-20,51-60");
+    /// assert_eq!(set.pad_sides(5, 2).to_string(), "6-22,46-62");
+    /// assert_eq!(set.pad_sides(20, 0).to_string(), "-9-20,31-60");
+    /// ```
+    pub fn pad_sides(&self, left: T, right: T) -> Self {
+        self.resize_spans(left, right, true)
+    }
+
+    /// The `left` integers below and `right` above each span, not in the set itself
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("11-20,51-60");
+    /// assert_eq!(set.flank(5, 2).to_string(), "6-10,21-22,46-50,61-62");
+    /// assert_eq!(set.flank(0, 40).to_string(), "21-50,61-100");
+    /// ```
+    pub fn flank(&self, left: T, right: T) -> Self {
+        self.pad_sides(left, right).diff(self)
+    }
+
+    /// Elements within `lower-upper`, as in bounding a set by a chromosome
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("-9-20,31-60");
+    /// assert_eq!(set.clip(1, 50).to_string(), "1-20,31-50");
+    /// ```
+    pub fn clip(&self, lower: T, upper: T) -> Self {
+        let first = self.span_at_or_before(lower).unwrap_or(0);
+
+        Self::from_pairs(
+            self.spans()
+                .skip(first)
+                .take_while(|&(l, _)| l <= upper)
+                .filter_map(|(l, u)| {
+                    let (l, u) = (l.max(lower), u.min(upper));
+                    if l <= u {
+                        Some((l, u))
+                    } else {
+                        None
+                    }
+                }),
+        )
     }
 
-    pub fn excise(&self, min_len: i32) -> Self {
-        let mut new = IntSpan::new();
+    pub fn excise(&self, min_len: T) -> Self {
+        let mut new = Self::new();
 
         for i in 0..self.span_size() {
             let lower = *self.edges.get(i * 2).unwrap();
-            let upper = *self.edges.get(i * 2 + 1).unwrap() - 1;
+            let upper = *self.edges.get(i * 2 + 1).unwrap() - T::ONE;
 
-            let span_len = upper - lower + 1;
+            let span_len = upper - lower + T::ONE;
             if span_len >= min_len {
                 new.add_pair(lower, upper);
             }
@@ -1030,15 +1938,88 @@ impl IntSpan {
         new
     }
 
-    pub fn fill(&self, max_len: i32) -> Self {
+    /// Keeps the spans for which `f(lower, upper)` returns true
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let mut set = IntSpan::from("1-5,11-12,21-30,41");
+    /// set.retain_spans(|lower, _| lower > 10);
+    /// assert_eq!(set.to_string(), "11-12,21-30,41");
+    /// set.retain_spans(|lower, upper| (upper - lower + 1) % 2 == 0);
+    /// assert_eq!(set.to_string(), "11-12,21-30");
+    /// ```
+    pub fn retain_spans<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T, T) -> bool,
+    {
+        let mut edges: Vec<T> = Vec::with_capacity(self.edges.len());
+        for (lower, upper) in self.spans() {
+            if f(lower, upper) {
+                edges.push(lower);
+                edges.push(upper + T::ONE);
+            }
+        }
+
+        self.prefix = None;
+        self.edges = edges;
+    }
+
+    /// The `n` largest spans. Among spans of equal length, the leftmost ones are kept
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-5,11-12,21-30,41-45");
+    /// assert_eq!(set.largest(2).to_string(), "1-5,21-30");
+    /// assert_eq!(set.largest(10), set);
+    /// ```
+    pub fn largest(&self, n: usize) -> Self {
+        let mut lengths: Vec<(T, usize)> = self
+            .spans()
+            .enumerate()
+            .map(|(i, (lower, upper))| (upper - lower + T::ONE, i))
+            .collect();
+        // longer first, then leftmost
+        lengths.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut kept: Vec<bool> = vec![false; lengths.len()];
+        for &(_, i) in lengths.iter().take(n) {
+            kept[i] = true;
+        }
+
+        let mut new = self.copy();
+        let mut i = 0;
+        new.retain_spans(|_, _| {
+            i += 1;
+            kept[i - 1]
+        });
+        new
+    }
+
+    /// Spans of `min_len` to `max_len` integers, inclusive
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-5,11-12,21-30,41-45");
+    /// assert_eq!(set.spans_between(3, 5).to_string(), "1-5,41-45");
+    /// ```
+    pub fn spans_between(&self, min_len: T, max_len: T) -> Self {
+        let mut new = self.copy();
+        new.retain_spans(|lower, upper| {
+            let span_len = upper - lower + T::ONE;
+            span_len >= min_len && span_len <= max_len
+        });
+        new
+    }
+
+    pub fn fill(&self, max_len: T) -> Self {
         let mut new = self.copy();
         let holes = self.holes();
 
         for i in 0..holes.span_size() {
             let lower = *holes.edges.get(i * 2).unwrap();
-            let upper = *holes.edges.get(i * 2 + 1).unwrap() - 1;
+            let upper = *holes.edges.get(i * 2 + 1).unwrap() - T::ONE;
 
-            let span_len = upper - lower + 1;
+            let span_len = upper - lower + T::ONE;
             if span_len <= max_len {
                 new.add_pair(lower, upper);
             }
@@ -1135,6 +2116,79 @@ mod span {
         assert_eq!(IntSpan::from("1-3").trim(2).cardinality(), 0);
     }
 
+    #[test]
+    fn filter_spans() {
+        // runlist n expLargest
+        let tests = vec![
+            ("-", 1, "-"),
+            ("1-5", 0, "-"),
+            ("1-5", 1, "1-5"),
+            ("1-5,11-12,21-30,41-45", 1, "21-30"),
+            ("1-5,11-12,21-30,41-45", 3, "1-5,21-30,41-45"),
+            ("1,3,5,7", 2, "1,3"),
+        ];
+        for (runlist, n, expected) in tests {
+            assert_eq!(IntSpan::from(runlist).largest(n).to_string(), expected);
+        }
+
+        // runlist min max expBetween
+        let tests = vec![
+            ("-", 1, 10, "-"),
+            ("1-5,11-12,21-30,41", 1, 1, "41"),
+            ("1-5,11-12,21-30,41", 2, 5, "1-5,11-12"),
+            ("1-5,11-12,21-30,41", 10, 100, "21-30"),
+            ("1-5,11-12,21-30,41", 6, 9, "-"),
+        ];
+        for (runlist, min, max, expected) in tests {
+            let set = IntSpan::from(runlist);
+            assert_eq!(set.spans_between(min, max).to_string(), expected);
+        }
+
+        // same as excise
+        let set = IntSpan::from("1-5,9-10,12-13,15");
+        let mut retained = set.clone();
+        retained.retain_spans(|lower, upper| upper - lower + 1 >= 2);
+        assert_eq!(retained, set.excise(2));
+
+        // cached index dropped
+        let mut set = IntSpan::from("1-5,11-12");
+        set.build_index();
+        set.retain_spans(|lower, _| lower > 10);
+        assert_eq!(set.at(1), 11);
+    }
+
+    #[test]
+    fn sides() {
+        // runlist left right expPad expTrim expFlank
+        let tests = vec![
+            ("-", 2, 3, "-", "-", "-"),
+            ("1-10", 0, 0, "1-10", "1-10", "-"),
+            ("1-10", 2, 3, "-1-13", "3-7", "-1-0,11-13"),
+            ("1-10", 5, 0, "-4-10", "6-10", "-4-0"),
+            ("1-10,15-20", 0, 4, "1-24", "1-6,15-16", "11-14,21-24"),
+            ("1-10,15-20", 3, 3, "-2-23", "4-7", "-2-0,11-14,21-23"),
+            ("1-10,15-20", 6, 0, "-5-20", "7-10", "-5-0,11-14"),
+        ];
+
+        for (runlist, left, right, exp_pad, exp_trim, exp_flank) in tests {
+            let set = IntSpan::from(runlist);
+            assert_eq!(set.pad_sides(left, right).to_string(), exp_pad);
+            assert_eq!(set.trim_sides(left, right).to_string(), exp_trim);
+            assert_eq!(set.flank(left, right).to_string(), exp_flank);
+        }
+
+        // infinite ends stay put
+        let set = IntSpan::from(format!("{}-10", IntSpan::new().get_neg_inf()));
+        assert_eq!(set.pad_sides(5, 5), set.pad_sides(0, 5));
+        assert_eq!(set.flank(5, 5).to_string(), "11-15");
+
+        // clip
+        let set = IntSpan::from("1-10,15-20,30");
+        assert_eq!(set.clip(5, 17).to_string(), "5-10,15-17");
+        assert_eq!(set.clip(21, 29).to_string(), "-");
+        assert_eq!(set.clip(30, 100).to_string(), "30");
+    }
+
     #[test]
     fn excise_fill() {
         // runlist n expExcise expFill
@@ -1165,39 +2219,1127 @@ mod span {
 }
 
 //----------------------------------------------------------
-// TODO: Inter-set operations
+// Iterators
 //----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// Iterates over spans as `(lower, upper)` pairs, without allocating
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5,7-9");
+    /// let spans: Vec<(i32, i32)> = set.spans().collect();
+    /// assert_eq!(spans, vec![(1, 3), (5, 5), (7, 9)]);
+    /// assert_eq!(set.spans().rev().next(), Some((7, 9)));
+    /// assert_eq!(set.spans().len(), 3);
+    /// ```
+    pub fn spans(&self) -> Spans<'_, T> {
+        Spans {
+            edges: &self.edges,
+            front: 0,
+            back: self.span_size(),
+        }
+    }
 
-//----------------------------------------------------------
-// TODO: Islands
-//----------------------------------------------------------
+    /// Iterates over elements in ascending order, without allocating
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5");
+    /// assert_eq!(set.iter().collect::<Vec<i32>>(), vec![1, 2, 3, 5]);
+    /// assert_eq!(set.iter().rev().collect::<Vec<i32>>(), vec![5, 3, 2, 1]);
+    ///
+    /// let mut sum = 0;
+    /// for n in &set {
+    ///     sum += n;
+    /// }
+    /// assert_eq!(sum, 11);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            spans: self.spans(),
+            front: None,
+            back: None,
+        }
+    }
+}
 
-//----------------------------------------------------------
-// Aliases
-//----------------------------------------------------------
-impl IntSpan {
-    pub fn size(&self) -> i32 {
-        self.cardinality()
+/// Iterator over the spans of an `IntSpan`, created by `spans()`
+#[derive(Clone)]
+pub struct Spans<'a, T> {
+    edges: &'a [T],
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T: Coord> Spans<'a, T> {
+    fn span(&self, i: usize) -> (T, T) {
+        (self.edges[i * 2], self.edges[i * 2 + 1] - T::ONE)
     }
+}
 
-    pub fn runlist(&self) -> String {
-        self.to_string()
+impl<'a, T: Coord> Iterator for Spans<'a, T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.span(self.front - 1))
     }
 
-    pub fn elements(&self) -> Vec<i32> {
-        self.to_vec()
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.back.min(self.front.saturating_add(n));
+        self.next()
     }
 }
 
-//----------------------------------------------------------
-// Private methods
-//----------------------------------------------------------
+impl<'a, T: Coord> DoubleEndedIterator for Spans<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.span(self.back))
+    }
+}
 
-impl IntSpan {
-    fn find_pos(&self, val: i32, mut low: usize) -> usize {
-        let mut high = self.edge_size();
+impl<'a, T: Coord> ExactSizeIterator for Spans<'a, T> {}
 
-        while low < high {
+/// Iterator over the elements of an `IntSpan`, created by `iter()`
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    spans: Spans<'a, T>,
+    // partially consumed spans at both ends, always lower <= upper
+    front: Option<(T, T)>,
+    back: Option<(T, T)>,
+}
+
+impl<'a, T: Coord> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front.is_none() {
+            self.front = self.spans.next().or_else(|| self.back.take());
+        }
+
+        let (lower, upper) = self.front?;
+        self.front = if lower < upper {
+            Some((lower + T::ONE, upper))
+        } else {
+            None
+        };
+
+        Some(lower)
+    }
+}
+
+impl<'a, T: Coord> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.back.is_none() {
+            self.back = self.spans.next_back().or_else(|| self.front.take());
+        }
+
+        let (lower, upper) = self.back?;
+        self.back = if lower < upper {
+            Some((lower, upper - T::ONE))
+        } else {
+            None
+        };
+
+        Some(upper)
+    }
+}
+
+impl<'a, T: Coord> IntoIterator for &'a GenericIntSpan<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Collects elements in any order
+///
+/// ```
+/// # use intspan::IntSpan;
+/// let set: IntSpan = vec![5, 1, 2, 3, 9].into_iter().collect();
+/// assert_eq!(set.to_string(), "1-3,5,9");
+/// ```
+impl<T: Coord> FromIterator<T> for GenericIntSpan<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut new = Self::new();
+        new.extend(iter);
+        new
+    }
+}
+
+/// Collects `(lower, upper)` pairs in any order
+///
+/// ```
+/// # use intspan::IntSpan;
+/// let set: IntSpan = vec![(7, 9), (1, 3), (4, 5)].into_iter().collect();
+/// assert_eq!(set.to_string(), "1-5,7-9");
+/// ```
+impl<T: Coord> FromIterator<(T, T)> for GenericIntSpan<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        Self::from_pairs(iter)
+    }
+}
+
+/// ```
+/// # use intspan::IntSpan;
+/// let mut set = IntSpan::from("1-3");
+/// set.extend(vec![5, 4]);
+/// set.extend(vec![(10, 12)]);
+/// assert_eq!(set.to_string(), "1-5,10-12");
+/// ```
+impl<T: Coord> Extend<T> for GenericIntSpan<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let ints: Vec<T> = iter.into_iter().collect();
+        self.add_vec(&ints);
+    }
+}
+
+impl<T: Coord> Extend<(T, T)> for GenericIntSpan<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        self.merge(&Self::from_pairs(iter));
+    }
+}
+
+#[cfg(test)]
+mod iter {
+    use super::*;
+
+    #[test]
+    fn spans_and_elements() {
+        let tests = vec!["-", "1", "1-5", "1-3,5,7-9", "-5--1,1-2"];
+
+        for runlist in tests {
+            let set = IntSpan::from(runlist);
+
+            let spans: Vec<(i32, i32)> = set.spans().collect();
+            let ranges: Vec<i32> = spans.iter().flat_map(|&(l, u)| vec![l, u]).collect();
+            assert_eq!(ranges, set.ranges(), "{}", runlist);
+
+            let mut rev: Vec<(i32, i32)> = set.spans().rev().collect();
+            rev.reverse();
+            assert_eq!(rev, spans);
+
+            assert_eq!(set.iter().collect::<Vec<i32>>(), set.to_vec());
+            let mut rev: Vec<i32> = set.iter().rev().collect();
+            rev.reverse();
+            assert_eq!(rev, set.to_vec());
+
+            let collected: IntSpan = set.iter().collect();
+            assert_eq!(collected.to_string(), set.to_string());
+            let collected: IntSpan = set.spans().collect();
+            assert_eq!(collected.to_string(), set.to_string());
+        }
+    }
+
+    #[test]
+    fn both_ends() {
+        let set = IntSpan::from("1-3,5,7-8");
+
+        // meet in the middle of a span
+        let mut iter = set.iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(8));
+        assert_eq!(iter.next_back(), Some(7));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut spans = set.spans();
+        assert_eq!(spans.next(), Some((1, 3)));
+        assert_eq!(spans.next_back(), Some((7, 8)));
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans.next_back(), Some((5, 5)));
+        assert_eq!(spans.next(), None);
+    }
+
+    #[test]
+    fn infinite() {
+        let mut set = IntSpan::new();
+        set.invert();
+
+        let head: Vec<i32> = set.iter().take(2).collect();
+        assert_eq!(head, vec![set.get_neg_inf(), set.get_neg_inf() + 1]);
+        assert_eq!(set.iter().next_back(), Some(set.get_pos_inf()));
+    }
+}
+
+//----------------------------------------------------------
+// Windows
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// Windows of `size` integers every `step`, starting at `min()`. Each window holds only the
+    /// elements of the set within it, windows falling in holes are skipped and the last one may
+    /// be shorter
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10,21-25");
+    /// let windows: Vec<String> = set.sliding(10, 5).map(|w| w.to_string()).collect();
+    /// assert_eq!(windows, vec!["1-10", "6-10", "21-25"]);
+    /// ```
+    pub fn sliding(&self, size: T, step: T) -> Windows<'_, T> {
+        Windows::new(Cow::Borrowed(self), size, step, false)
+    }
+
+    /// Adjacent windows of `size` integers, `sliding(size, size)`
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10,21-25");
+    /// let windows: Vec<String> = set.tile(10).map(|w| w.to_string()).collect();
+    /// assert_eq!(windows, vec!["1-10", "21-25"]);
+    /// ```
+    pub fn tile(&self, size: T) -> Windows<'_, T> {
+        self.sliding(size, size)
+    }
+
+    /// Like `sliding()`, but `size` and `step` count elements of the set, so windows jump over
+    /// holes and all but the last hold `size` elements
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10,21-25");
+    /// let windows: Vec<String> = set.sliding_members(8, 4).map(|w| w.to_string()).collect();
+    /// assert_eq!(windows, vec!["1-8", "5-10,21-22", "9-10,21-25"]);
+    /// ```
+    pub fn sliding_members(&self, size: T, step: T) -> Windows<'_, T> {
+        let set = if self.is_indexed() {
+            Cow::Borrowed(self)
+        } else {
+            let mut set = self.clone();
+            set.build_index();
+            Cow::Owned(set)
+        };
+
+        Windows::new(set, size, step, true)
+    }
+
+    /// Adjacent windows of `size` elements, `sliding_members(size, size)`
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10,21-25");
+    /// let windows: Vec<String> = set.tile_members(6).map(|w| w.to_string()).collect();
+    /// assert_eq!(windows, vec!["1-6", "7-10,21-22", "23-25"]);
+    /// ```
+    pub fn tile_members(&self, size: T) -> Windows<'_, T> {
+        self.sliding_members(size, size)
+    }
+}
+
+/// Iterator over windows of an `IntSpan`, created by `sliding()`, `tile()` and their `_members`
+/// counterparts
+pub struct Windows<'a, T: Coord> {
+    set: Cow<'a, GenericIntSpan<T>>,
+    size: T,
+    step: T,
+    by_members: bool,
+    // start of the next window, a coordinate or a 1-based index of elements
+    next: Option<T>,
+}
+
+impl<'a, T: Coord> Windows<'a, T> {
+    fn new(set: Cow<'a, GenericIntSpan<T>>, size: T, step: T, by_members: bool) -> Self {
+        if size < T::ONE || step < T::ONE {
+            panic!("Window size and step must be positive");
+        }
+
+        let next = if set.is_empty() {
+            None
+        } else if by_members {
+            Some(T::ONE)
+        } else {
+            Some(set.min())
+        };
+
+        Self {
+            set,
+            size,
+            step,
+            by_members,
+            next,
+        }
+    }
+}
+
+impl<'a, T: Coord> Windows<'a, T> {
+    // The first window start from `start` on, stepping by `step`, that reaches an element of the
+    // set, so holes are jumped over instead of stepped through
+    fn skip_hole(&self, start: T) -> Option<T> {
+        let edges = &self.set.edges;
+        let pos = self.set.find_pos(start, 0);
+        let first = if pos & 1 == 0 {
+            *edges.get(pos)?
+        } else if edges[pos] == start {
+            *edges.get(pos + 1)?
+        } else {
+            return Some(start);
+        };
+
+        let reach = start.to_i128() + self.size.to_i128() - 1;
+        if reach >= first.to_i128() {
+            return Some(start);
+        }
+        let step = self.step.to_i128();
+        let steps = (first.to_i128() - reach + step - 1) / step;
+        T::from_i128(start.to_i128() + steps * step)
+    }
+}
+
+impl<'a, T: Coord> Iterator for Windows<'a, T> {
+    type Item = GenericIntSpan<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.next?;
+            let last = if self.by_members {
+                self.set.cached_cardinality()
+            } else {
+                self.set.max()
+            };
+            let end = start.saturating_add(self.size - T::ONE).min(last);
+            self.next = if end == last {
+                None
+            } else if self.by_members {
+                start.checked_add(self.step).filter(|&n| n <= last)
+            } else {
+                start
+                    .checked_add(self.step)
+                    .and_then(|n| self.skip_hole(n))
+                    .filter(|&n| n <= last)
+            };
+
+            let window = if self.by_members {
+                self.set.clip(self.set.at_pos(start), self.set.at_pos(end))
+            } else {
+                self.set.clip(start, end)
+            };
+            if !window.is_empty() {
+                return Some(window);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod window {
+    use super::*;
+
+    #[test]
+    fn test_windows() {
+        // runlist, size, step, sliding, sliding_members
+        let tests = vec![
+            ("-", 5, 5, vec![], vec![]),
+            ("1-3", 5, 5, vec!["1-3"], vec!["1-3"]),
+            ("1-10", 5, 5, vec!["1-5", "6-10"], vec!["1-5", "6-10"]),
+            (
+                "1-10",
+                4,
+                3,
+                vec!["1-4", "4-7", "7-10"],
+                vec!["1-4", "4-7", "7-10"],
+            ),
+            (
+                "1-10",
+                4,
+                4,
+                vec!["1-4", "5-8", "9-10"],
+                vec!["1-4", "5-8", "9-10"],
+            ),
+            (
+                "1-3,21-23",
+                5,
+                5,
+                vec!["1-3", "21-23"],
+                vec!["1-3,21-22", "23"],
+            ),
+            (
+                "1-3,7-9,21",
+                4,
+                2,
+                vec!["1-3", "3", "7-8", "7-9", "9", "21"],
+                vec!["1-3,7", "3,7-9", "8-9,21"],
+            ),
+            (
+                "-5--1,1-2",
+                3,
+                3,
+                vec!["-5--3", "-2--1", "1-2"],
+                vec!["-5--3", "-2--1,1", "2"],
+            ),
+        ];
+
+        for (runlist, size, step, sliding, members) in tests {
+            let set = IntSpan::from(runlist);
+
+            let windows: Vec<String> = set.sliding(size, step).map(|w| w.to_string()).collect();
+            assert_eq!(windows, sliding, "{} {} {}", runlist, size, step);
+
+            let windows: Vec<String> = set
+                .sliding_members(size, step)
+                .map(|w| w.to_string())
+                .collect();
+            assert_eq!(windows, members, "{} {} {}", runlist, size, step);
+        }
+    }
+
+    #[test]
+    fn tiles_cover_the_set() {
+        let set = IntSpan::from("1-3,7-9,21,30-100,200-299");
+
+        for size in 1..20 {
+            let mut union = IntSpan::new();
+            for window in set.tile(size) {
+                assert!(window.cover().cardinality() <= size);
+                assert!(union.intersect(&window).is_empty());
+                union.merge(&window);
+            }
+            assert_eq!(union, set);
+
+            let mut union = IntSpan::new();
+            let tiles: Vec<IntSpan> = set.tile_members(size).collect();
+            for (i, window) in tiles.iter().enumerate() {
+                if i + 1 < tiles.len() {
+                    assert_eq!(window.cardinality(), size);
+                }
+                union.merge(window);
+            }
+            assert_eq!(union, set);
+        }
+    }
+
+    #[test]
+    fn windows_skip_holes() {
+        let set = IntSpan::from("1-5,2000000000-2000000005");
+
+        let windows: Vec<String> = set.sliding(10, 5).map(|w| w.to_string()).collect();
+        assert_eq!(windows, vec!["1-5", "2000000000", "2000000000-2000000005"]);
+
+        let windows: Vec<String> = set.tile(3).map(|w| w.to_string()).collect();
+        assert_eq!(
+            windows,
+            vec![
+                "1-3",
+                "4-5",
+                "2000000000-2000000001",
+                "2000000002-2000000004",
+                "2000000005"
+            ]
+        );
+
+        // same as stepping through every window
+        let set = IntSpan::from("1,100,200-210,500-502");
+        for size in 1..15 {
+            for step in 1..15 {
+                let mut expected: Vec<IntSpan> = Vec::new();
+                let mut start = set.min();
+                while start <= set.max() {
+                    let window = set.clip(start, start + size - 1);
+                    if !window.is_empty() {
+                        expected.push(window);
+                    }
+                    if start + size > set.max() {
+                        break;
+                    }
+                    start += step;
+                }
+                let windows: Vec<IntSpan> = set.sliding(size, step).collect();
+                assert_eq!(windows, expected, "{} {}", size, step);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Window size and step must be positive")]
+    fn panic_window() {
+        let set = IntSpan::from("1-10");
+        println!("{:?}", set.sliding(5, 0).count());
+    }
+}
+
+//----------------------------------------------------------
+// Sampling
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// `n` distinct elements drawn uniformly, in ascending order. Panics if `n` is larger than the
+    /// cardinality
+    ///
+    /// Results are reproducible with a seeded `rng`.
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let set = IntSpan::from("1-10,101-110");
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let elements = set.sample(5, &mut rng);
+    /// assert_eq!(elements.len(), 5);
+    /// assert!(elements.iter().all(|&n| set.contains(n)));
+    /// assert_eq!(elements, set.sample(5, &mut StdRng::seed_from_u64(42)));
+    /// ```
+    pub fn sample<R: Rng + ?Sized>(&self, n: T, rng: &mut R) -> Vec<T> {
+        let cardinality = self.cached_cardinality();
+        if n > cardinality {
+            panic!("Can't sample {} from {} elements", n, cardinality);
+        }
+
+        // Floyd's algorithm on indices, without replacement
+        let mut indices: BTreeSet<T> = BTreeSet::new();
+        let mut j = cardinality - n + T::ONE;
+        while j <= cardinality {
+            let t = rng.gen_range(T::ONE..=j);
+            if !indices.insert(t) {
+                indices.insert(j);
+            }
+            j += T::ONE;
+        }
+
+        let prefix = self.prefix();
+        indices
+            .into_iter()
+            .map(|index| {
+                let i = prefix[1..].partition_point(|&p| p < index);
+                self.edges[i * 2] + (index - prefix[i] - T::ONE)
+            })
+            .collect()
+    }
+
+    /// One interval of `len` elements lying entirely within an island, drawn uniformly among all
+    /// such intervals. `None` if no island is long enough
+    pub fn sample_interval<R: Rng + ?Sized>(&self, len: T, rng: &mut R) -> Option<(T, T)> {
+        self.sample_intervals(len, T::ONE, rng).pop()
+    }
+
+    /// `n` intervals of `len` elements, each lying entirely within an island. Intervals are drawn
+    /// independently, so they may overlap. Empty if no island is long enough
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let set = IntSpan::from("1-10,101-103");
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// for (lower, upper) in set.sample_intervals(5, 10, &mut rng) {
+    ///     assert_eq!(upper - lower + 1, 5);
+    ///     assert!(lower >= 1 && upper <= 10);
+    /// }
+    /// assert!(set.sample_intervals(20, 10, &mut rng).is_empty());
+    /// ```
+    pub fn sample_intervals<R: Rng + ?Sized>(&self, len: T, n: T, rng: &mut R) -> Vec<(T, T)> {
+        if len < T::ONE {
+            panic!("Interval length must be positive");
+        }
+
+        // numbers of valid starts, cumulated over islands
+        let mut lowers: Vec<T> = vec![];
+        let mut starts: Vec<T> = vec![T::ZERO];
+        for (lower, upper) in self.spans() {
+            if upper - lower + T::ONE >= len {
+                lowers.push(lower);
+                starts.push(*starts.last().unwrap() + (upper - lower + T::ONE - len + T::ONE));
+            }
+        }
+
+        let total = *starts.last().unwrap();
+        if total == T::ZERO {
+            return vec![];
+        }
+
+        let mut intervals = vec![];
+        let mut i = T::ZERO;
+        while i < n {
+            let r = rng.gen_range(T::ONE..=total);
+            let island = starts[1..].partition_point(|&p| p < r);
+            let lower = lowers[island] + (r - starts[island] - T::ONE);
+            intervals.push((lower, lower + len - T::ONE));
+            i += T::ONE;
+        }
+
+        intervals
+    }
+}
+
+#[cfg(test)]
+mod sample {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sample() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let set = IntSpan::from("-5--1,11-15,101");
+        for n in 0..=11 {
+            let elements = set.sample(n, &mut rng);
+            assert_eq!(elements.len(), n as usize);
+
+            let mut sorted = elements.clone();
+            sorted.dedup();
+            assert_eq!(sorted, elements, "distinct and sorted");
+            assert!(elements.iter().all(|&e| set.contains(e)));
+        }
+        assert_eq!(set.sample(11, &mut rng), set.to_vec());
+
+        // every element is reachable
+        let mut seen = IntSpan::new();
+        for _ in 0..500 {
+            seen.add_vec(&set.sample(1, &mut rng));
+        }
+        assert_eq!(seen, set);
+    }
+
+    #[test]
+    fn test_sample_intervals() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let set = IntSpan::from("1-3,11-20,31-34");
+        let mut seen = IntSpan::new();
+        for (lower, upper) in set.sample_intervals(4, 500, &mut rng) {
+            assert_eq!(upper - lower, 3);
+            assert_eq!(
+                set.find_span(lower),
+                set.find_span(upper),
+                "within an island"
+            );
+            seen.add_pair(lower, upper);
+        }
+        assert_eq!(seen.to_string(), "11-20,31-34");
+
+        assert_eq!(set.sample_interval(10, &mut rng), Some((11, 20)));
+        assert_eq!(set.sample_interval(11, &mut rng), None);
+        assert!(IntSpan::new().sample_intervals(1, 5, &mut rng).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't sample 4 from 3 elements")]
+    fn panic_sample() {
+        let mut rng = StdRng::seed_from_u64(1);
+        IntSpan::from("1-3").sample(4, &mut rng);
+    }
+}
+
+//----------------------------------------------------------
+// Inter-set operations
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// Size of the intersection
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10");
+    /// assert_eq!(set.overlap(&IntSpan::from("5-15")), 6);
+    /// assert_eq!(set.overlap(&IntSpan::from("11-15")), 0);
+    /// ```
+    pub fn overlap(&self, other: &Self) -> T {
+        self.intersect(other).cardinality()
+    }
+
+    /// Distance between two sets. Overlapping sets give the negated size of their overlap, adjacent
+    /// ones give 1, and `None` if either set is empty. As an `i128`, so it's signed for unsigned
+    /// coordinates too
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10");
+    /// assert_eq!(set.distance(&IntSpan::from("5-15")), Some(-6));
+    /// assert_eq!(set.distance(&IntSpan::from("11-15")), Some(1));
+    /// assert_eq!(set.distance(&IntSpan::from("20-25,40")), Some(10));
+    /// assert_eq!(set.distance(&IntSpan::new()), None);
+    /// ```
+    pub fn distance(&self, other: &Self) -> Option<i128> {
+        if self.is_empty() || other.is_empty() {
+            return None;
+        }
+
+        let overlap = self.overlap(other);
+        if overlap > T::ZERO {
+            return Some(-overlap.to_i128());
+        }
+
+        // Spans of the two sets don't overlap. The closest pair lies next to each other when all
+        // spans are sorted together
+        let mut spans: Vec<(T, T, bool)> = self
+            .spans()
+            .map(|(lower, upper)| (lower, upper, true))
+            .chain(other.spans().map(|(lower, upper)| (lower, upper, false)))
+            .collect();
+        spans.sort_unstable();
+
+        spans
+            .windows(2)
+            .filter(|w| w[0].2 != w[1].2)
+            .map(|w| (w[1].0 - w[0].1).to_i128())
+            .min()
+    }
+
+    /// Jaccard index, the size of the intersection over that of the union. See `Similarity` for
+    /// the other metrics
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-10");
+    /// assert_eq!(set.jaccard(&IntSpan::from("6-15")), 1.0 / 3.0);
+    /// assert_eq!(set.dice(&IntSpan::from("6-15")), 0.5);
+    /// assert_eq!(set.overlap_coefficient(&IntSpan::from("1-100")), 1.0);
+    /// assert_eq!(set.bp_distance(&IntSpan::from("6-15")), 10);
+    /// ```
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        Similarity::of(self, other).jaccard()
+    }
+
+    /// Sørensen–Dice coefficient
+    pub fn dice(&self, other: &Self) -> f64 {
+        Similarity::of(self, other).dice()
+    }
+
+    /// Size of the intersection over that of the smaller set
+    pub fn overlap_coefficient(&self, other: &Self) -> f64 {
+        Similarity::of(self, other).overlap_coefficient()
+    }
+
+    /// Number of integers in exactly one of the sets
+    pub fn bp_distance(&self, other: &Self) -> T {
+        self.xor(other).cardinality()
+    }
+}
+
+#[cfg(test)]
+mod inter_set {
+    use super::*;
+
+    #[test]
+    fn test_overlap_distance() {
+        // A B overlap distance
+        let tests = vec![
+            ("1-5", "-", 0, None),
+            ("1-5", "1-5", 5, Some(-5)),
+            ("1-5", "3-9", 3, Some(-3)),
+            ("1-5", "6-9", 0, Some(1)),
+            ("1-5", "9", 0, Some(4)),
+            ("1-5,20-25", "9,15", 0, Some(4)),
+            ("1-5,20-25", "9,17", 0, Some(3)),
+            ("1-5,20-25", "4-21", 4, Some(-4)),
+            ("-10--5,20", "0", 0, Some(5)),
+        ];
+
+        for (a, b, overlap, distance) in tests {
+            let ia = IntSpan::from(a);
+            let ib = IntSpan::from(b);
+
+            assert_eq!(ia.overlap(&ib), overlap, "{} {}", a, b);
+            assert_eq!(ib.overlap(&ia), overlap, "{} {}", a, b);
+            assert_eq!(ia.distance(&ib), distance, "{} {}", a, b);
+            assert_eq!(ib.distance(&ia), distance, "{} {}", a, b);
+
+            // without negative coordinates
+            if a.starts_with('-') {
+                continue;
+            }
+            let ua = GenericIntSpan::<u32>::from(a);
+            let ub = GenericIntSpan::<u32>::from(b);
+            assert_eq!(ua.distance(&ub), distance, "{} {}", a, b);
+            let ua = GenericIntSpan::<u64>::from(a);
+            let ub = GenericIntSpan::<u64>::from(b);
+            assert_eq!(ub.distance(&ua), distance, "{} {}", a, b);
+        }
+    }
+}
+
+//----------------------------------------------------------
+// Islands
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    /// Each span as a set of its own
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5,7-9");
+    /// let islands: Vec<String> = set.islands().iter().map(|s| s.to_string()).collect();
+    /// assert_eq!(islands, vec!["1-3", "5", "7-9"]);
+    /// ```
+    pub fn islands(&self) -> Vec<Self> {
+        self.spans()
+            .map(|(lower, upper)| Self::from_pairs(vec![(lower, upper)]))
+            .collect()
+    }
+
+    /// The island at `index`, counting from 1. Negative indices count from the last island
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5,7-9");
+    /// assert_eq!(set.at_island(2).unwrap().to_string(), "5");
+    /// assert_eq!(set.at_island(-1).unwrap().to_string(), "7-9");
+    /// assert!(set.at_island(0).is_none());
+    /// assert!(set.at_island(4).is_none());
+    /// ```
+    pub fn at_island(&self, index: isize) -> Option<Self> {
+        let count = self.span_size();
+        if index == 0 || index.unsigned_abs() > count {
+            return None;
+        }
+
+        let i = if index > 0 {
+            index.unsigned_abs() - 1
+        } else {
+            count - index.unsigned_abs()
+        };
+        let (lower, upper) = self.spans().nth(i).unwrap();
+        Some(Self::from_pairs(vec![(lower, upper)]))
+    }
+
+    /// The island containing `n`, empty when `n` isn't in the set
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5,7-9");
+    /// assert_eq!(set.find_islands(8).to_string(), "7-9");
+    /// assert_eq!(set.find_islands(4).to_string(), "-");
+    /// ```
+    pub fn find_islands(&self, n: T) -> Self {
+        match self.find_span(n) {
+            Some(span) => Self::from_pairs(vec![span]),
+            None => Self::new(),
+        }
+    }
+
+    /// Islands overlapping with `other`
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,5,7-9");
+    /// assert_eq!(set.find_islands_set(&IntSpan::from("2-5")).to_string(), "1-3,5");
+    /// assert_eq!(set.find_islands_set(&IntSpan::from("4,6")).to_string(), "-");
+    /// ```
+    pub fn find_islands_set(&self, other: &Self) -> Self {
+        Self::from_pairs(
+            self.spans()
+                .filter(|&(lower, upper)| other.overlaps_pair(lower, upper)),
+        )
+    }
+
+    /// The nearest island(s) not containing `n`. Both neighbours are returned if they are equally
+    /// distant
+    ///
+    /// ```
+    /// # use intspan::IntSpan;
+    /// let set = IntSpan::from("1-3,7-9,12");
+    /// assert_eq!(set.nearest_island(5).to_string(), "1-3,7-9");
+    /// assert_eq!(set.nearest_island(10).to_string(), "7-9");
+    /// assert_eq!(set.nearest_island(8).to_string(), "12");
+    /// assert_eq!(set.nearest_island(20).to_string(), "12");
+    /// ```
+    pub fn nearest_island(&self, n: T) -> Self {
+        self.nearest_island_set(&Self::from_pairs(vec![(n, n)]))
+    }
+
+    /// The nearest island(s) to any island of `other`, skipping overlapping ones. Equally distant
+    /// islands are all returned
+    pub fn nearest_island_set(&self, other: &Self) -> Self {
+        let mut min_d: Option<T> = None;
+        let mut nearest: Vec<(T, T)> = vec![];
+
+        for (lower, upper) in other.spans() {
+            let candidates = self
+                .span_before(lower)
+                .map(|span| (span, lower - span.1))
+                .into_iter()
+                .chain(self.span_after(upper).map(|span| (span, span.0 - upper)));
+            for (span, d) in candidates {
+                match min_d {
+                    Some(min) if d > min => {}
+                    Some(min) if d == min => nearest.push(span),
+                    _ => {
+                        min_d = Some(d);
+                        nearest = vec![span];
+                    }
+                }
+            }
+        }
+
+        Self::from_pairs(nearest)
+    }
+
+    // Whether any element of lower-upper is in the set
+    fn overlaps_pair(&self, lower: T, upper: T) -> bool {
+        match self.span_at_or_before(upper) {
+            Some(i) => self.edges[i * 2 + 1] > lower,
+            None => false,
+        }
+    }
+
+    // The last span ending before `n`
+    fn span_before(&self, n: T) -> Option<(T, T)> {
+        // upper edges are exclusive, those not larger than n end before it
+        let ended = self.edges.partition_point(|&e| e <= n) / 2;
+        let i = ended.checked_sub(1)?;
+
+        Some((self.edges[i * 2], self.edges[i * 2 + 1] - T::ONE))
+    }
+
+    // The first span starting after `n`
+    fn span_after(&self, n: T) -> Option<(T, T)> {
+        let i = self.span_at_or_before(n).map_or(0, |i| i + 1);
+        if i < self.span_size() {
+            Some((self.edges[i * 2], self.edges[i * 2 + 1] - T::ONE))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod island {
+    use super::*;
+
+    #[test]
+    fn test_find_islands() {
+        // runlist, n, island, nearest
+        let tests = vec![
+            ("-", 1, "-", "-"),
+            ("1-5", 1, "1-5", "-"),
+            ("1-5", 6, "-", "1-5"),
+            ("1-5,8-9", 6, "-", "1-5"),
+            ("1-5,8-9", 7, "-", "8-9"),
+            ("1-5,9-10", 7, "-", "1-5,9-10"),
+            ("1-5,9-10,20", 9, "9-10", "1-5"),
+            ("1-5,9-10,20", 15, "-", "9-10,20"),
+            ("1-5,9-10,20", 30, "-", "20"),
+            ("1-5,9-10,20", -30, "-", "1-5"),
+        ];
+
+        for (runlist, n, island, nearest) in tests {
+            let set = IntSpan::from(runlist);
+            assert_eq!(set.find_islands(n).to_string(), island, "{} {}", runlist, n);
+            assert_eq!(
+                set.nearest_island(n).to_string(),
+                nearest,
+                "{} {}",
+                runlist,
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_islands_set() {
+        // runlist, other, islands, nearest
+        let tests = vec![
+            ("-", "1-5", "-", "-"),
+            ("1-5", "-", "-", "-"),
+            ("1-5,9-10,20", "4-9", "1-5,9-10", "20"),
+            ("1-5,9-10,20", "7,15", "-", "1-5,9-10"),
+            ("1-5,9-10,20", "1-30", "1-5,9-10,20", "-"),
+            ("1-5,9-10,20", "12,25-30", "-", "9-10"),
+        ];
+
+        for (runlist, other, islands, nearest) in tests {
+            let set = IntSpan::from(runlist);
+            let other = IntSpan::from(other);
+            assert_eq!(set.find_islands_set(&other).to_string(), islands);
+            assert_eq!(set.nearest_island_set(&other).to_string(), nearest);
+        }
+    }
+
+    #[test]
+    fn test_at_island() {
+        let set = IntSpan::from("1-5,9-10,20");
+        let islands = set.islands();
+        assert_eq!(islands.len(), 3);
+
+        for i in 1..=3 {
+            assert_eq!(set.at_island(i).unwrap(), islands[i as usize - 1]);
+            assert_eq!(set.at_island(-i).unwrap(), islands[3 - i as usize]);
+        }
+        assert!(set.at_island(4).is_none());
+        assert!(set.at_island(-4).is_none());
+        assert!(set.at_island(isize::MIN).is_none());
+        assert!(set.at_island(isize::MAX).is_none());
+        assert!(IntSpan::new().at_island(1).is_none());
+        assert!(IntSpan::new().at_island(isize::MIN).is_none());
+        assert!(IntSpan::new().islands().is_empty());
+    }
+}
+
+//----------------------------------------------------------
+// Aliases
+//----------------------------------------------------------
+impl<T: Coord> GenericIntSpan<T> {
+    pub fn size(&self) -> T {
+        self.cardinality()
+    }
+
+    pub fn runlist(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn elements(&self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+//----------------------------------------------------------
+// Private methods
+//----------------------------------------------------------
+
+impl<T: Coord> GenericIntSpan<T> {
+    // Moves the lower ends of spans outwards (pad) or inwards (trim) by `left` and the upper ends by
+    // `right`. Infinite ends stay put and finite ones never pass the infinities.
+    fn resize_spans(&self, left: T, right: T, outwards: bool) -> Self {
+        let mut new = Self::new();
+
+        for i in 0..self.span_size() {
+            let mut lower = *self.edges.get(i * 2).unwrap();
+            let mut upper = *self.edges.get(i * 2 + 1).unwrap() - T::ONE;
+
+            if lower != self.get_neg_inf() {
+                lower = if outwards {
+                    lower.saturating_sub(left).max(self.get_neg_inf())
+                } else {
+                    lower.saturating_add(left)
+                };
+            }
+            if upper != self.get_pos_inf() {
+                if !outwards && upper < self.get_neg_inf().saturating_add(right) {
+                    continue;
+                }
+                upper = if outwards {
+                    upper.saturating_add(right).min(self.get_pos_inf())
+                } else {
+                    upper.saturating_sub(right)
+                };
+            }
+
+            if lower <= upper {
+                new.add_pair(lower, upper);
+            }
+        }
+
+        new
+    }
+
+    fn find_pos(&self, val: T, mut low: usize) -> usize {
+        let mut high = self.edge_size();
+
+        while low < high {
             let mid = (low + high) / 2;
             if val < *self.edges.get(mid).unwrap() {
                 high = mid;
@@ -1211,10 +3353,10 @@ impl IntSpan {
         low
     }
 
-    fn list_to_ranges(&self, ints: &Vec<i32>) -> Vec<i32> {
-        let mut ranges: Vec<i32> = Vec::new();
+    fn list_to_ranges(&self, ints: &[T]) -> Vec<T> {
+        let mut ranges: Vec<T> = Vec::new();
 
-        let mut ints = ints.clone();
+        let mut ints = ints.to_vec();
         ints.sort_unstable();
         ints.dedup();
 
@@ -1223,7 +3365,7 @@ impl IntSpan {
 
         while pos < len {
             let mut end = pos + 1;
-            while (end < len) && (ints[end] <= ints[end - 1] + 1) {
+            while (end < len) && (ints[end] <= ints[end - 1] + T::ONE) {
                 end += 1;
             }
             ranges.push(ints[pos]);
@@ -1234,86 +3376,298 @@ impl IntSpan {
         ranges
     }
 
-    fn runlist_to_ranges(&self, runlist: &String) -> Vec<i32> {
-        let mut ranges: Vec<i32> = Vec::new();
+    // Lexes a runlist into pairs of lower and upper bounds, without checking their order
+    fn runlist_to_ranges(runlist: &str) -> Result<Vec<T>, ParseError> {
+        let mut ranges: Vec<T> = Vec::new();
 
         let bytes = runlist.as_bytes();
-
-        let radix = 10;
-        let mut idx = 0; // index in runlist
         let len = bytes.len();
 
-        let mut lower_is_neg = false;
-        let mut upper_is_neg = false;
-        let mut in_upper = false;
+        let mut start = 0; // offset of the current run
+        while start <= len {
+            let end = bytes[start..]
+                .iter()
+                .position(|&ch| ch == b',')
+                .map_or(len, |p| start + p);
+
+            if start == end {
+                // at the comma ending the empty run, or at the one before the last run
+                let offset = if end < len {
+                    end
+                } else {
+                    end.saturating_sub(1)
+                };
+                return Err(ParseError::new(
+                    ParseErrorKind::MissingNumber,
+                    bytes.get(offset).copied().unwrap_or(b','),
+                    offset,
+                    runlist,
+                ));
+            }
 
-        while idx < len {
-            let mut i = 0; // index in one run
-            if *bytes.get(idx).unwrap() == b'-' {
-                lower_is_neg = true;
-                i += 1;
+            let (lower, mut pos) = Self::lex_number(runlist, start, end)?;
+            let upper = if pos < end && bytes[pos] == b'-' {
+                let (upper, next) = Self::lex_number(runlist, pos + 1, end)?;
+                pos = next;
+                upper
+            } else {
+                lower
+            };
+            if pos < end {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidDigit,
+                    bytes[pos],
+                    pos,
+                    runlist,
+                ));
             }
 
-            // ported from Java Integer.parseInt()
-            let mut lower: i32 = 0;
-            let mut upper: i32 = 0;
+            ranges.push(lower);
+            ranges.push(upper);
 
-            while idx + i < len {
-                let ch = bytes[idx + i];
-                if ch >= b'0' && ch <= b'9' {
-                    if !in_upper {
-                        lower *= radix;
-                        lower -= (ch as char).to_digit(10).unwrap() as i32;
-                    } else {
-                        upper *= radix;
-                        upper -= (ch as char).to_digit(10).unwrap() as i32;
-                    }
-                } else if ch == b'-' {
-                    if !in_upper {
-                        in_upper = true;
-                        if *bytes.get(idx + i + 1).unwrap() == b'-' {
-                            upper_is_neg = true;
-                        }
-                    }
-                } else if ch == b',' {
-                    i += 1;
-                    break; // end of run
-                } else {
-                    panic!(
-                        "Number format error: {} at {} of {}",
-                        ch as char,
-                        idx + i,
-                        runlist
-                    );
-                }
+            // start next run
+            start = end + 1;
+        }
 
-                i += 1;
-            }
+        Ok(ranges)
+    }
+
+    // The smallest number of runlists. For unsigned types 0 is the negative infinity, and
+    // coordinates start at 1
+    fn lowest() -> T {
+        if T::NEG_INF == T::ZERO {
+            T::ONE
+        } else {
+            T::NEG_INF
+        }
+    }
+
+    // Lexes a number, optionally negative, from `pos` to at most `end`. Returns it and the offset
+    // after it. Overflows are reported at the last digit
+    fn lex_number(runlist: &str, mut pos: usize, end: usize) -> Result<(T, usize), ParseError> {
+        let bytes = runlist.as_bytes();
+
+        let is_neg = pos < end && bytes[pos] == b'-';
+        if is_neg {
+            pos += 1;
+        }
 
-            if !in_upper {
-                ranges.push(if lower_is_neg { lower } else { -lower }); // add lower
-                ranges.push(if lower_is_neg { lower } else { -lower }); // add lower again
+        let digits = bytes[pos..end]
+            .iter()
+            .take_while(|ch| ch.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return Err(if pos < end && bytes[pos] != b'-' {
+                ParseError::new(ParseErrorKind::InvalidDigit, bytes[pos], pos, runlist)
             } else {
-                ranges.push(if lower_is_neg { lower } else { -lower }); // add lower
-                ranges.push(if upper_is_neg { upper } else { -upper }); // add upper
+                // at the dash taking the place of the number, or the one before the end of the run
+                let offset = if pos < end { pos } else { pos - 1 };
+                ParseError::new(
+                    ParseErrorKind::MissingNumber,
+                    bytes[offset],
+                    offset,
+                    runlist,
+                )
+            });
+        }
+
+        let last = pos + digits - 1;
+        let radix = T::from(10u8);
+        let mut acc = Some(T::ZERO);
+        for ch in &bytes[pos..=last] {
+            acc = acc
+                .and_then(|v| v.checked_mul(radix))
+                .and_then(|v| v.checked_add(T::from(ch - b'0')));
+        }
+        let n = if is_neg {
+            acc.and_then(|v| v.checked_neg())
+        } else {
+            acc
+        };
+
+        match n {
+            Some(n) if n >= Self::lowest() && n < T::POS_INF => Ok((n, last + 1)),
+            _ => Err(ParseError::new(
+                ParseErrorKind::Overflow,
+                bytes[last],
+                last,
+                runlist,
+            )),
+        }
+    }
+}
+
+/// The kind of error encountered while parsing a runlist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character other than digits, `-` and `,`
+    InvalidDigit,
+    /// A number beyond the infinities of `IntSpan`, or 0 for unsigned types
+    Overflow,
+    /// The lower bound of a run is larger than the upper bound
+    BadOrder,
+    /// An empty run, or a `-` without a number after it
+    MissingNumber,
+}
+
+/// Error returned by `IntSpan::try_from()` and `str::parse::<IntSpan>()`
+///
+/// ```
+/// # use intspan::{IntSpan, ParseErrorKind};
+/// use std::convert::TryFrom;
+///
+/// let err = IntSpan::try_from("1-3,5a").err().unwrap();
+/// assert_eq!(err.kind(), ParseErrorKind::InvalidDigit);
+/// assert_eq!(err.byte(), b'a');
+/// assert_eq!(err.offset(), 5);
+/// assert_eq!(err.runlist(), "1-3,5a");
+/// assert_eq!(err.to_string(), "Number format error: a at 5 of 1-3,5a");
+///
+/// let err = IntSpan::try_from("1-99999999999").err().unwrap();
+/// assert_eq!(err.kind(), ParseErrorKind::Overflow);
+///
+/// let err = IntSpan::try_from("1,5-3").err().unwrap();
+/// assert_eq!(err.kind(), ParseErrorKind::BadOrder);
+/// assert_eq!(err.offset(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    byte: u8,
+    offset: usize,
+    runlist: String,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, byte: u8, offset: usize, runlist: &str) -> Self {
+        Self {
+            kind,
+            byte,
+            offset,
+            runlist: runlist.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// The offending byte
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    /// Byte offset of the offending byte in the runlist
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn runlist(&self) -> &str {
+        &self.runlist
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::InvalidDigit => write!(
+                f,
+                "Number format error: {} at {} of {}",
+                self.byte as char, self.offset, self.runlist
+            ),
+            ParseErrorKind::Overflow => write!(
+                f,
+                "Number overflow: {} at {} of {}",
+                self.byte as char, self.offset, self.runlist
+            ),
+            ParseErrorKind::BadOrder => {
+                write!(f, "Bad order: run at {} of {}", self.offset, self.runlist)
+            }
+            ParseErrorKind::MissingNumber => {
+                write!(f, "Missing number at {} of {}", self.offset, self.runlist)
             }
+        }
+    }
+}
 
-            // reset boolean flags
-            lower_is_neg = false;
-            upper_is_neg = false;
-            in_upper = false;
+impl std::error::Error for ParseError {}
+
+/// Fallible version of `IntSpan::from()`
+///
+/// ```
+/// # use intspan::IntSpan;
+/// use std::convert::TryFrom;
+///
+/// let set = IntSpan::try_from("1-3,5").unwrap();
+/// assert_eq!(set.to_string(), "1-3,5");
+/// assert!(IntSpan::try_from("1-3,x").is_err());
+///
+/// let set: IntSpan = "-".parse().unwrap();
+/// assert!(set.is_empty());
+/// ```
+impl<T: Coord> TryFrom<&str> for GenericIntSpan<T> {
+    type Error = ParseError;
+
+    fn try_from(runlist: &str) -> Result<Self, Self::Error> {
+        let mut new = Self::new();
 
-            // start next run
-            idx += i;
+        // skip empty runlist
+        if runlist.is_empty() || runlist == EMPTY_STRING {
+            return Ok(new);
         }
 
-        ranges
+        let ranges = Self::runlist_to_ranges(runlist)?;
+        for (i, pair) in ranges.chunks(2).enumerate() {
+            if pair[0] > pair[1] {
+                // offset of the i-th run
+                let offset = if i == 0 {
+                    0
+                } else {
+                    runlist.match_indices(',').nth(i - 1).unwrap().0 + 1
+                };
+                return Err(ParseError::new(
+                    ParseErrorKind::BadOrder,
+                    runlist.as_bytes()[offset],
+                    offset,
+                    runlist,
+                ));
+            }
+        }
+        new.add_ranges(&ranges);
+
+        Ok(new)
+    }
+}
+
+impl<T: Coord> FromStr for GenericIntSpan<T> {
+    type Err = ParseError;
+
+    fn from_str(runlist: &str) -> Result<Self, Self::Err> {
+        Self::try_from(runlist)
     }
 }
 
-impl fmt::Display for IntSpan {
+impl<T: Coord> fmt::Display for GenericIntSpan<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())?;
+        if self.is_empty() {
+            return write!(f, "{}", EMPTY_STRING);
+        }
+
+        for i in 0..self.span_size() {
+            let lower = *self.edges.get(i * 2).unwrap();
+            let upper = *self.edges.get(i * 2 + 1).unwrap() - T::ONE;
+
+            if i != 0 {
+                write!(f, ",")?;
+            }
+
+            if lower == upper {
+                write!(f, "{}", lower)?;
+            } else {
+                write!(f, "{}-{}", lower, upper)?;
+            }
+        }
+
         Ok(())
     }
 }
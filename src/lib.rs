@@ -1,11 +1,29 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod binary;
+mod bitmap;
+mod coord;
 mod coverage;
+mod error;
+mod expr;
 mod intspan;
+mod order;
 mod range;
+#[cfg(feature = "serde")]
+mod serialize;
+mod similarity;
 mod utils;
+pub use crate::binary::*;
+pub use crate::bitmap::*;
+pub use crate::coord::*;
 pub use crate::coverage::*;
+pub use crate::error::*;
+pub use crate::expr::*;
 pub use crate::intspan::*;
+pub use crate::order::*;
 pub use crate::range::*;
+#[cfg(feature = "serde")]
+pub use crate::serialize::*;
+pub use crate::similarity::*;
 pub use crate::utils::*;
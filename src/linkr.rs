@@ -16,7 +16,7 @@ fn main() {
         .subcommand(cmd_linkr::clean::make_subcommand());
 
     // Check which subcomamnd the user ran...
-    let _res = match app.get_matches().subcommand() {
+    let result = match app.get_matches().subcommand() {
         ("circos", Some(sub_matches)) => cmd_linkr::circos::execute(sub_matches),
         ("sort", Some(sub_matches)) => cmd_linkr::sort::execute(sub_matches),
         ("merge", Some(sub_matches)) => cmd_linkr::merge::execute(sub_matches),
@@ -24,4 +24,10 @@ fn main() {
         ("clean", Some(sub_matches)) => cmd_linkr::clean::execute(sub_matches),
         (_, _) => unreachable!(),
     };
+
+    // a one-line diagnostic naming the file, and the line where known
+    if let Err(err) = result {
+        eprintln!("linkr: {}", err);
+        std::process::exit(err.exit_code());
+    }
 }
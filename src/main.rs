@@ -21,10 +21,16 @@ fn main() {
         .subcommand(cmd::cover::make_subcommand())
         .subcommand(cmd::gff::make_subcommand())
         .subcommand(cmd::convert::make_subcommand())
-        .subcommand(cmd::range::make_subcommand());
+        .subcommand(cmd::range::make_subcommand())
+        .subcommand(cmd::window::make_subcommand())
+        .subcommand(cmd::shuffle::make_subcommand())
+        .subcommand(cmd::flank::make_subcommand())
+        .subcommand(cmd::similarity::make_subcommand())
+        .subcommand(cmd::nway::make_subcommand())
+        .subcommand(cmd::eval::make_subcommand());
 
     // Check which subcomamnd the user ran...
-    let _res = match app.get_matches().subcommand() {
+    let result = match app.get_matches().subcommand() {
         ("genome", Some(sub_matches)) => cmd::genome::execute(sub_matches),
         ("some", Some(sub_matches)) => cmd::some::execute(sub_matches),
         ("merge", Some(sub_matches)) => cmd::merge::execute(sub_matches),
@@ -38,13 +44,24 @@ fn main() {
         ("gff", Some(sub_matches)) => cmd::gff::execute(sub_matches),
         ("convert", Some(sub_matches)) => cmd::convert::execute(sub_matches),
         ("range", Some(sub_matches)) => cmd::range::execute(sub_matches),
+        ("window", Some(sub_matches)) => cmd::window::execute(sub_matches),
+        ("shuffle", Some(sub_matches)) => cmd::shuffle::execute(sub_matches),
+        ("flank", Some(sub_matches)) => cmd::flank::execute(sub_matches),
+        ("similarity", Some(sub_matches)) => cmd::similarity::execute(sub_matches),
+        ("nway", Some(sub_matches)) => cmd::nway::execute(sub_matches),
+        ("eval", Some(sub_matches)) => cmd::eval::execute(sub_matches),
         (_, _) => unreachable!(),
     };
+
+    // one line, except for expressions pointing at the mistake
+    if let Err(err) = result {
+        eprintln!("intspan: {}", err);
+        std::process::exit(err.exit_code());
+    }
 }
 
 // TODO: CI releases
 // TODO: ovlp.rs
-// TODO: wrap IO with Result
 // TODO: satisfy clippy
 // TODO: impl various traits
 
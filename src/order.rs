@@ -0,0 +1,344 @@
+//! Orders of chromosome names.
+//!
+//! ```
+//! use intspan::{natural_cmp, ChrOrder};
+//! use std::cmp::Ordering;
+//!
+//! assert_eq!(natural_cmp("chr2", "chr10"), Ordering::Less);
+//!
+//! let order = ChrOrder::from_chrs(vec!["chrX", "chr1", "chr2"]);
+//! let mut chrs = vec!["chr2", "chrUn", "chr1", "chrM", "chrX"];
+//! chrs.sort_by(|a, b| order.compare(a, b));
+//! assert_eq!(chrs, vec!["chrX", "chr1", "chr2", "chrM", "chrUn"]);
+//! ```
+
+use crate::{read_lines, Coord, Error, GenericRange, Result};
+use serde_yaml::{Mapping, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Compares strings with runs of digits compared by their numeric values, so `chr2 < chr10`
+///
+/// Ties, like `chr01` and `chr1`, are broken lexically.
+///
+/// ```
+/// # use intspan::natural_cmp;
+/// # use std::cmp::Ordering;
+/// assert_eq!(natural_cmp("chr2", "chr10"), Ordering::Less);
+/// assert_eq!(natural_cmp("chr10", "chr10_random"), Ordering::Less);
+/// assert_eq!(natural_cmp("chr1", "chr01"), Ordering::Greater);
+/// assert_eq!(natural_cmp("II", "IV"), Ordering::Less);
+/// ```
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut xs = chunks(a);
+    let mut ys = chunks(b);
+
+    loop {
+        let ord = match (xs.next(), ys.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let is_num = |s: &str| s.as_bytes()[0].is_ascii_digit();
+                if is_num(x) && is_num(y) {
+                    let x = x.trim_start_matches('0');
+                    let y = y.trim_start_matches('0');
+                    x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+                } else {
+                    x.cmp(y)
+                }
+            }
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+// Runs of digits and runs of others
+fn chunks(s: &str) -> impl Iterator<Item = &str> {
+    let bytes = s.as_bytes();
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        if start >= bytes.len() {
+            return None;
+        }
+        let is_digit = bytes[start].is_ascii_digit();
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit() == is_digit)
+            .count();
+        let chunk = &s[start..start + len];
+        start += len;
+        Some(chunk)
+    })
+}
+
+// Value of a canonical Roman numeral, optionally after `chr`
+fn roman(chr: &str) -> Option<u32> {
+    let numeral = chr.strip_prefix("chr").unwrap_or(chr);
+
+    let mut value: i64 = 0;
+    let digits: Vec<i64> = numeral
+        .chars()
+        .map(|ch| match ch {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            'C' => 100,
+            'D' => 500,
+            'M' => 1000,
+            _ => 0,
+        })
+        .collect();
+    for (i, digit) in digits.iter().enumerate() {
+        if *digit == 0 {
+            return None;
+        }
+        if digits.get(i + 1).is_some_and(|next| next > digit) {
+            value -= digit;
+        } else {
+            value += digit;
+        }
+    }
+
+    // so that `IIII` or `IIV` aren't numerals
+    if value > 0 && to_roman(value as u32) == numeral {
+        Some(value as u32)
+    } else {
+        None
+    }
+}
+
+fn to_roman(mut n: u32) -> String {
+    let table = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut numeral = String::new();
+    for (value, symbols) in table.iter() {
+        while n >= *value {
+            numeral += symbols;
+            n -= value;
+        }
+    }
+    numeral
+}
+
+/// How chromosomes are ordered
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ChrOrder {
+    /// Byte-wise, the order of `BTreeMap` keys
+    #[default]
+    Lexical,
+    /// See `natural_cmp()`
+    Natural,
+    /// Roman numerals, optionally after `chr`, by their values, like the yeast `I` to `XVI`.
+    /// Others go after, in natural order
+    Roman,
+    /// By ranks, as listed in a chr.sizes file. Unlisted chromosomes go after, in natural order
+    Ranked(HashMap<String, usize>),
+}
+
+impl ChrOrder {
+    /// `lexical`, `natural`, `roman`, or the path of a chr.sizes file
+    ///
+    /// ```
+    /// # use intspan::ChrOrder;
+    /// assert_eq!(ChrOrder::parse("roman").unwrap(), ChrOrder::Roman);
+    /// assert!(ChrOrder::parse("tests/resources/S288c.chr.sizes").is_ok());
+    /// assert!(ChrOrder::parse("romans").is_err());
+    /// ```
+    pub fn parse(order: &str) -> Result<Self> {
+        match order {
+            "lexical" => Ok(ChrOrder::Lexical),
+            "natural" => Ok(ChrOrder::Natural),
+            "roman" => Ok(ChrOrder::Roman),
+            _ if Path::new(order).is_file() => Self::from_sizes(order),
+            _ => Err(Error::args(format!("Invalid chromosome order: {}", order))),
+        }
+    }
+
+    /// Ranked as listed
+    pub fn from_chrs<I, S>(chrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut rank_of: HashMap<String, usize> = HashMap::new();
+        for chr in chrs {
+            let rank = rank_of.len();
+            rank_of.entry(chr.into()).or_insert(rank);
+        }
+
+        ChrOrder::Ranked(rank_of)
+    }
+
+    /// Ranked by the lines of a chr.sizes file
+    ///
+    /// ```
+    /// # use intspan::ChrOrder;
+    /// # use std::cmp::Ordering;
+    /// let order = ChrOrder::from_sizes("tests/resources/S288c.chr.sizes").unwrap();
+    /// assert_eq!(order.compare("IV", "IX"), Ordering::Less);
+    /// assert_eq!(order.compare("XVI", "Mito"), Ordering::Less);
+    /// ```
+    pub fn from_sizes(input: &str) -> Result<Self> {
+        let lines = read_lines(input)?;
+        let chrs = lines
+            .iter()
+            .filter_map(|line| line.split('\t').next())
+            .filter(|chr| !chr.is_empty());
+
+        Ok(Self::from_chrs(chrs))
+    }
+
+    /// ```
+    /// # use intspan::ChrOrder;
+    /// let mut chrs = vec!["Mito", "IX", "chrII", "IV", "I", "X"];
+    /// chrs.sort_by(|a, b| ChrOrder::Roman.compare(a, b));
+    /// assert_eq!(chrs, vec!["I", "chrII", "IV", "IX", "X", "Mito"]);
+    /// chrs.sort_by(|a, b| ChrOrder::Lexical.compare(a, b));
+    /// assert_eq!(chrs, vec!["I", "IV", "IX", "Mito", "X", "chrII"]);
+    /// ```
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            ChrOrder::Lexical => a.cmp(b),
+            ChrOrder::Natural => natural_cmp(a, b),
+            ChrOrder::Roman => match (roman(a), roman(b)) {
+                (Some(x), Some(y)) => x.cmp(&y).then_with(|| natural_cmp(a, b)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => natural_cmp(a, b),
+            },
+            ChrOrder::Ranked(rank_of) => match (rank_of.get(a), rank_of.get(b)) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => natural_cmp(a, b),
+            },
+        }
+    }
+
+    /// Sorted in this order
+    pub fn sorted<'a, I>(&self, chrs: I) -> Vec<&'a String>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        let mut chrs: Vec<&String> = chrs.into_iter().collect();
+        chrs.sort_by(|a, b| self.compare(a, b));
+        chrs
+    }
+
+    /// Keys of `yaml` and of its nested mappings in this order. These are chromosomes, or names of
+    /// sets in files of multiple sets
+    ///
+    /// ```
+    /// # use intspan::{set2yaml, ChrOrder, IntSpan};
+    /// # use std::collections::BTreeMap;
+    /// let mut set: BTreeMap<String, IntSpan> = BTreeMap::new();
+    /// set.insert("II".to_string(), IntSpan::from("1-10"));
+    /// set.insert("IX".to_string(), IntSpan::from("1-10"));
+    /// set.insert("V".to_string(), IntSpan::from("1-10"));
+    ///
+    /// let yaml = ChrOrder::Roman.yaml(&set2yaml(&set));
+    /// let s = serde_yaml::to_string(&yaml).unwrap();
+    /// assert_eq!(s, "---\nII: 1-10\nV: 1-10\nIX: 1-10");
+    /// ```
+    pub fn yaml(&self, yaml: &BTreeMap<String, Value>) -> Value {
+        let mut mapping = Mapping::new();
+        for key in self.sorted(yaml.keys()) {
+            let value = match &yaml[key] {
+                Value::Mapping(nested) => {
+                    let nested: Option<BTreeMap<String, Value>> = nested
+                        .iter()
+                        .map(|(k, v)| Some((k.as_str()?.to_string(), v.clone())))
+                        .collect();
+                    match nested {
+                        Some(nested) => self.yaml(&nested),
+                        None => yaml[key].clone(),
+                    }
+                }
+                value => value.clone(),
+            };
+            mapping.insert(Value::String(key.to_string()), value);
+        }
+
+        Value::Mapping(mapping)
+    }
+
+    /// Ranges by chromosomes in this order, then as `Ord` of `Range`
+    ///
+    /// ```
+    /// # use intspan::{ChrOrder, Range};
+    /// let order = ChrOrder::from_chrs(vec!["II", "I"]);
+    /// let mut ranges = vec![Range::from_str("I:1-10"), Range::from_str("II:5-10")];
+    /// ranges.sort_by(|a, b| order.compare_ranges(a, b));
+    /// assert_eq!(ranges[0].to_string(), "II:5-10");
+    /// ```
+    pub fn compare_ranges<T: Coord>(&self, a: &GenericRange<T>, b: &GenericRange<T>) -> Ordering {
+        self.compare(a.chr(), b.chr()).then_with(|| a.cmp(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn romans() {
+        for n in 1..4000 {
+            assert_eq!(roman(&to_roman(n)), Some(n));
+        }
+        for numeral in &["", "chr", "IIII", "IIV", "VX", "iv", "Mito", "2micron"] {
+            assert_eq!(roman(numeral), None, "{}", numeral);
+        }
+        assert_eq!(roman("chrXVI"), Some(16));
+    }
+
+    #[test]
+    fn natural() {
+        let mut chrs = vec![
+            "chr10",
+            "chr2",
+            "chrX",
+            "chr1",
+            "chr1_random",
+            "chr01",
+            "chr",
+            "10",
+            "9",
+            "",
+        ];
+        chrs.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            chrs,
+            vec![
+                "",
+                "9",
+                "10",
+                "chr",
+                "chr01",
+                "chr1",
+                "chr1_random",
+                "chr2",
+                "chr10",
+                "chrX"
+            ]
+        );
+    }
+}
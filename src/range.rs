@@ -1,18 +1,30 @@
-use crate::IntSpan;
+use crate::{natural_cmp, Coord, GenericIntSpan};
 use regex::Regex;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Default, Clone)]
-pub struct Range {
+/// A genomic range, `name.chr(strand):start-end`, with coordinates of type `T`
+#[derive(Default, Clone, Debug)]
+pub struct GenericRange<T> {
     name: String,
     chr: String,
     strand: String,
-    start: i32,
-    end: i32,
+    start: T,
+    end: T,
     others: BTreeMap<String, String>,
+    // the text after the coordinates as read, written back as long as `others` matches it
+    text: String,
 }
 
-impl Range {
+/// `Range` with `i32` coordinates, the default one
+pub type Range = GenericRange<i32>;
+
+/// `Range` with `i64` coordinates
+pub type Range64 = GenericRange<i64>;
+
+impl<T: Coord> GenericRange<T> {
     // Immutable accessors
     pub fn name(&self) -> &String {
         &self.name
@@ -23,26 +35,69 @@ impl Range {
     pub fn strand(&self) -> &String {
         &self.strand
     }
-    pub fn start(&self) -> &i32 {
+    pub fn start(&self) -> &T {
         &self.start
     }
-    pub fn end(&self) -> &i32 {
+    pub fn end(&self) -> &T {
         &self.end
     }
+    /// `key=value` attributes after the coordinates, with the remaining free text under the key
+    /// `""`. A repeated key keeps its last value
+    ///
+    /// `to_string()` writes back the text as read. Once `others` is changed, it writes the
+    /// attributes sorted by keys and then the description, separated by single spaces
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let mut range = Range::from_str("I:1-100 b=2 a=1 dubious ORF");
+    /// assert_eq!(range.to_string(), "I:1-100 b=2 a=1 dubious ORF");
+    /// range.others_mut().insert("c".to_string(), "3".to_string());
+    /// assert_eq!(range.to_string(), "I:1-100 a=1 b=2 c=3 dubious ORF");
+    /// ```
+    pub fn others(&self) -> &BTreeMap<String, String> {
+        &self.others
+    }
+    /// The free text after the coordinates, e.g. the description of a FASTA header
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("S288c The baker's yeast");
+    /// assert_eq!(*range.chr(), "S288c");
+    /// assert_eq!(range.description().unwrap(), "The baker's yeast");
+    /// ```
+    pub fn description(&self) -> Option<&String> {
+        self.others.get("")
+    }
 
     // Mutable accessors
+    pub fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+    pub fn chr_mut(&mut self) -> &mut String {
+        &mut self.chr
+    }
     pub fn strand_mut(&mut self) -> &mut String {
         &mut self.strand
     }
+    pub fn start_mut(&mut self) -> &mut T {
+        &mut self.start
+    }
+    pub fn end_mut(&mut self) -> &mut T {
+        &mut self.end
+    }
+    pub fn others_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.others
+    }
 
     pub fn new() -> Self {
         Self {
             name: "".to_string(),
             chr: "".to_string(),
             strand: "".to_string(),
-            start: 0,
-            end: 0,
+            start: T::ZERO,
+            end: T::ZERO,
             others: BTreeMap::new(),
+            text: "".to_string(),
         }
     }
 
@@ -55,7 +110,7 @@ impl Range {
     /// # assert_eq!(*range.start(), 1);
     /// # assert_eq!(*range.end(), 100);
     /// ```
-    pub fn from<S>(chr: S, start: i32, end: i32) -> Self
+    pub fn from<S>(chr: S, start: T, end: T) -> Self
     where
         S: Into<String>,
     {
@@ -68,9 +123,35 @@ impl Range {
             start,
             end,
             others: BTreeMap::new(),
+            text: "".to_string(),
         }
     }
 
+    /// Constructed field by field
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::builder()
+    ///     .name("S288c")
+    ///     .chr("I")
+    ///     .strand("-")
+    ///     .start(27070)
+    ///     .end(29557)
+    ///     .other("gene", "YAL019W")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(range.to_string(), "S288c.I(-):27070-29557 gene=YAL019W");
+    ///
+    /// let range = Range::builder().chr("I").start(100).build().unwrap();
+    /// assert_eq!(range.to_string(), "I:100");
+    ///
+    /// let err = Range::builder().chr("I").start(100).end(50).build().err().unwrap();
+    /// assert_eq!(err.to_string(), "Inverted coordinates: I:100-50");
+    /// ```
+    pub fn builder() -> GenericRangeBuilder<T> {
+        GenericRangeBuilder { range: Self::new() }
+    }
+
     /// Constructed from string
     ///
     /// ```
@@ -89,7 +170,12 @@ impl Range {
     /// # assert_eq!(*range.name(), "S288c");
     /// # assert_eq!(*range.strand(), "-");
     /// # assert_eq!(range.to_string(), "S288c.I(-):27070-29557");
+    /// let range = Range::from_str("I:1-100 gene=YAL069W dubious ORF");
+    /// # assert_eq!(range.others()["gene"], "YAL069W");
+    /// # assert_eq!(range.description().unwrap(), "dubious ORF");
+    /// # assert_eq!(range.to_string(), "I:1-100 gene=YAL069W dubious ORF");
     /// ```
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str<S>(range: S) -> Self
     where
         S: Into<String>,
@@ -97,24 +183,12 @@ impl Range {
         let s = range.into();
 
         let mut new = Self::new();
-        new.decode(&s);
+        // lenient, keeps whatever was decoded
+        new.decode(&s).ok();
 
         new
     }
 
-    /// To string
-    ///
-    /// ```
-    /// # use intspan::Range;
-    /// let range = Range::from("I", 1, 100);
-    /// assert_eq!(range.to_string(), "I:1-100");
-    /// let range = Range::from("I", 100, 100);
-    /// assert_eq!(range.to_string(), "I:100");
-    /// ```
-    pub fn to_string(&self) -> String {
-        self.encode()
-    }
-
     /// Valid or not
     ///
     /// ```
@@ -127,7 +201,80 @@ impl Range {
     /// assert!(!range.is_valid());
     /// ```
     pub fn is_valid(&self) -> bool {
-        self.start != 0
+        self.start != T::ZERO
+    }
+
+    /// Fallible version of `from_str()`, rejecting anything but a well-formed range. The range
+    /// starts the string and is followed by whitespace, a `|` or the end of it
+    ///
+    /// ```
+    /// # use intspan::{Range, RangeErrorKind};
+    /// let range = Range::parse("S288c.I(-):27070-29557").unwrap();
+    /// assert_eq!(range.to_string(), "S288c.I(-):27070-29557");
+    ///
+    /// let err = Range::parse("I:500-100").err().unwrap();
+    /// assert_eq!(err.kind(), RangeErrorKind::Inverted);
+    /// assert_eq!(err.to_string(), "Inverted coordinates: I:500-100");
+    ///
+    /// assert_eq!(Range::parse("S288c").err().unwrap().kind(), RangeErrorKind::NoCoordinates);
+    /// assert_eq!(Range::parse("I:0-10").err().unwrap().kind(), RangeErrorKind::BadCoordinates);
+    /// assert_eq!(Range::parse("I:+5").err().unwrap().kind(), RangeErrorKind::BadCoordinates);
+    /// assert_eq!(Range::parse("I(x):1-10").err().unwrap().kind(), RangeErrorKind::BadStrand);
+    /// assert_eq!(Range::parse("I:1-3000000000").err().unwrap().kind(), RangeErrorKind::Overflow);
+    /// assert_eq!(Range::parse("I:1-2147483647").err().unwrap().kind(), RangeErrorKind::Overflow);
+    /// assert_eq!(Range::parse("I:1,000").err().unwrap().kind(), RangeErrorKind::BadCoordinates);
+    /// ```
+    pub fn parse(range: &str) -> Result<Self, RangeError> {
+        let mut new = Self::new();
+        let (start, end) = new.decode(range)?;
+
+        let err = |kind| Err(RangeError::new(kind, range));
+        let is_sep = |ch: char| ch.is_whitespace() || ch == '|';
+        if start != 0 || !range[end..].chars().next().is_none_or(is_sep) {
+            return err(RangeErrorKind::BadCoordinates);
+        }
+        new.validate(range)?;
+
+        Ok(new)
+    }
+
+    // Strand and coordinates of a well-formed range, errors quoting `range`
+    fn validate(&self, range: &str) -> Result<(), RangeError> {
+        let err = |kind| Err(RangeError::new(kind, range));
+        if !self.strand.is_empty() && self.strand != "+" && self.strand != "-" {
+            return err(RangeErrorKind::BadStrand);
+        }
+        if self.start < T::ONE {
+            return err(RangeErrorKind::BadCoordinates);
+        }
+        if self.start.max(self.end) > T::POS_INF - T::ONE {
+            return err(RangeErrorKind::Overflow);
+        }
+        if self.end < self.start {
+            return err(RangeErrorKind::Inverted);
+        }
+
+        Ok(())
+    }
+
+    /// Number of integers covered, 0 for invalid ranges
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// assert_eq!(Range::from_str("I:1-100").len(), 100);
+    /// assert_eq!(Range::from_str("I:100").len(), 1);
+    /// assert_eq!(Range::from_str("I").len(), 0);
+    /// ```
+    pub fn len(&self) -> T {
+        if self.is_valid() && self.end >= self.start {
+            self.end - self.start + T::ONE
+        } else {
+            T::ZERO
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == T::ZERO
     }
 
     /// IntSpan
@@ -139,13 +286,182 @@ impl Range {
     /// let range = Range::from_str("I:100");
     /// assert_eq!(range.intspan().to_string(), "100");
     /// ```
-    pub fn intspan(&self) -> IntSpan {
-        let mut intspan = IntSpan::new();
+    pub fn intspan(&self) -> GenericIntSpan<T> {
+        let mut intspan = GenericIntSpan::new();
         intspan.add_pair(self.start, self.end);
         intspan
     }
 
-    fn decode(&mut self, header: &String) {
+    /// Moved by `n` integers towards higher coordinates, or lower ones for negative `n`, regardless
+    /// of the strand. Starts stop at 1, `None` if the whole range falls before it
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("I(-):1001-2000");
+    /// assert_eq!(range.shift(100).unwrap().to_string(), "I(-):1101-2100");
+    /// assert_eq!(range.shift(-1500).unwrap().to_string(), "I(-):1-500");
+    /// assert!(range.shift(-2000).is_none());
+    /// ```
+    pub fn shift(&self, n: T) -> Option<Self> {
+        let end = self.end.saturating_add(n);
+        if end < T::ONE {
+            return None;
+        }
+
+        let mut new = self.clone();
+        new.start = self.start.saturating_add(n).max(T::ONE);
+        new.end = end;
+        Some(new)
+    }
+
+    /// Extended by `left` integers towards lower coordinates and `right` ones towards higher,
+    /// regardless of the strand. Starts stop at 1
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("I(-):1001-2000");
+    /// assert_eq!(range.extend(100, 10).to_string(), "I(-):901-2010");
+    /// assert_eq!(range.extend(5000, 0).to_string(), "I(-):1-2000");
+    /// ```
+    pub fn extend(&self, left: T, right: T) -> Self {
+        let mut new = self.clone();
+        new.start = self.start.saturating_sub(left).max(T::ONE);
+        new.end = self.end.saturating_add(right);
+        new
+    }
+
+    /// On the other strand. Ranges without a strand stay without
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// assert_eq!(Range::from_str("I(+):1-100").flip_strand().to_string(), "I(-):1-100");
+    /// assert_eq!(Range::from_str("I(-):1-100").flip_strand().to_string(), "I(+):1-100");
+    /// assert_eq!(Range::from_str("I:1-100").flip_strand().to_string(), "I:1-100");
+    /// ```
+    pub fn flip_strand(&self) -> Self {
+        let mut new = self.clone();
+        new.strand = match self.strand.as_str() {
+            "+" => "-".to_string(),
+            "-" => "+".to_string(),
+            other => other.to_string(),
+        };
+        new
+    }
+
+    /// The same name, chromosome, strand and attributes, spanning `intspan` from its min to its
+    /// max. An empty `intspan` gives an invalid range
+    ///
+    /// ```
+    /// # use intspan::{IntSpan, Range};
+    /// let range = Range::from_str("S288c.I(-):1-100");
+    /// let merged = range.with_intspan(&IntSpan::from("50-200,301-400"));
+    /// assert_eq!(merged.to_string(), "S288c.I(-):50-400");
+    /// assert!(!range.with_intspan(&IntSpan::new()).is_valid());
+    /// ```
+    pub fn with_intspan(&self, intspan: &GenericIntSpan<T>) -> Self {
+        let mut new = self.clone();
+        new.start = intspan.first().unwrap_or(T::ZERO);
+        new.end = intspan.last().unwrap_or(T::ZERO);
+        new
+    }
+
+    /// Extended by `upstream` and `downstream` integers, relative to the strand. Starts stop at 1.
+    /// Negative amounts count as 0
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("I(+):1001-2000");
+    /// assert_eq!(range.slop(100, 10).to_string(), "I(+):901-2010");
+    /// let range = Range::from_str("I(-):1001-2000");
+    /// assert_eq!(range.slop(100, 10).to_string(), "I(-):991-2100");
+    /// assert_eq!(range.slop(5000, 0).to_string(), "I(-):1001-7000");
+    /// assert_eq!(range.slop(0, 5000).to_string(), "I(-):1-2000");
+    /// assert_eq!(range.slop(-50, 0).to_string(), "I(-):1001-2000");
+    /// ```
+    pub fn slop(&self, upstream: T, downstream: T) -> Self {
+        let (left, right) = self.sides(upstream, downstream);
+
+        let mut new = self.clone();
+        new.start = self.start.saturating_sub(left).max(T::ONE);
+        new.end = self.end.saturating_add(right);
+        new
+    }
+
+    /// The `upstream` and `downstream` flanking regions, relative to the strand. A flank is `None`
+    /// if it is empty, lies before position 1 or beyond the largest coordinate. Negative amounts
+    /// count as 0
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("I(-):1001-2000");
+    /// let (up, down) = range.flank(100, 10);
+    /// assert_eq!(up.unwrap().to_string(), "I(-):2001-2100");
+    /// assert_eq!(down.unwrap().to_string(), "I(-):991-1000");
+    ///
+    /// let range = Range::from_str("I:1-100");
+    /// let (up, down) = range.flank(100, 10);
+    /// assert!(up.is_none());
+    /// assert_eq!(down.unwrap().to_string(), "I:101-110");
+    /// ```
+    pub fn flank(&self, upstream: T, downstream: T) -> (Option<Self>, Option<Self>) {
+        let (left, right) = self.sides(upstream, downstream);
+
+        let before = if left > T::ZERO && self.start > T::ONE {
+            let mut new = self.clone();
+            new.start = self.start.saturating_sub(left).max(T::ONE);
+            new.end = self.start - T::ONE;
+            Some(new)
+        } else {
+            None
+        };
+        let after = match self.end.checked_add(T::ONE) {
+            Some(start) if right > T::ZERO => {
+                let mut new = self.clone();
+                new.start = start;
+                new.end = self.end.saturating_add(right);
+                Some(new)
+            }
+            _ => None,
+        };
+
+        if self.strand == "-" {
+            (after, before)
+        } else {
+            (before, after)
+        }
+    }
+
+    /// Bounded by a chromosome of `size`, `None` if it starts beyond
+    ///
+    /// ```
+    /// # use intspan::Range;
+    /// let range = Range::from_str("I:901-1100");
+    /// assert_eq!(range.clip(1000).unwrap().to_string(), "I:901-1000");
+    /// assert!(range.clip(900).is_none());
+    /// ```
+    pub fn clip(&self, size: T) -> Option<Self> {
+        if self.start > size {
+            return None;
+        }
+
+        let mut new = self.clone();
+        new.end = self.end.min(size);
+        Some(new)
+    }
+
+    // Upstream and downstream as lower and upper sides, negatives as 0
+    fn sides(&self, upstream: T, downstream: T) -> (T, T) {
+        let upstream = upstream.max(T::ZERO);
+        let downstream = downstream.max(T::ZERO);
+        if self.strand == "-" {
+            (downstream, upstream)
+        } else {
+            (upstream, downstream)
+        }
+    }
+
+    // Returns the byte span of the matched range in `header`
+    fn decode(&mut self, header: &str) -> Result<(usize, usize), RangeError> {
         lazy_static! {
             static ref RE: Regex = Regex::new(
                 r"(?xi)
@@ -154,18 +470,25 @@ impl Range {
                 (?:\((?P<strand>.+)\))?
                 [:]                    # spacer
                 (?P<start>\d+)
-                [_\-]?                 # spacer
-                (?P<end>\d+)?
+                (?:[_\-]               # spacer
+                (?P<end>\d+))?
                 ",
             )
             .unwrap();
         }
 
-        let caps = match RE.captures(header.as_str()) {
+        let caps = match RE.captures(header) {
             Some(x) => x,
             None => {
-                self.chr = header.split(' ').next().unwrap().to_string();
-                return;
+                let chr = header.split(char::is_whitespace).next().unwrap();
+                self.chr = chr.to_string();
+                self.decode_others(&header[chr.len()..]);
+                let kind = if header.contains(':') {
+                    RangeErrorKind::BadCoordinates
+                } else {
+                    RangeErrorKind::NoCoordinates
+                };
+                return Err(RangeError::new(kind, header));
             }
         };
         let dict: HashMap<String, String> = RE
@@ -173,22 +496,55 @@ impl Range {
             .flatten()
             .filter_map(|n| Some((n.to_string(), caps.name(n)?.as_str().to_string())))
             .collect();
+        let coord = |value: &String| {
+            value
+                .parse::<T>()
+                .map_err(|_| RangeError::new(RangeErrorKind::Overflow, header))
+        };
         for key in dict.keys() {
             match key.as_str() {
                 "name" => self.name = dict.get(key).unwrap().to_owned(),
                 "chr" => self.chr = dict.get(key).unwrap().to_owned(),
                 "strand" => self.strand = dict.get(key).unwrap().to_owned(),
-                "start" => self.start = dict.get(key).unwrap().parse::<i32>().unwrap(),
-                "end" => self.end = dict.get(key).unwrap().parse::<i32>().unwrap(),
+                "start" => self.start = coord(dict.get(key).unwrap())?,
+                "end" => self.end = coord(dict.get(key).unwrap())?,
                 _ => {}
             }
         }
 
-        if self.start != 0 && self.end == 0 {
+        if self.start != T::ZERO && self.end == T::ZERO {
             self.end = self.start;
         }
 
-        //        eprintln!("{:#?}", &dict);
+        let matched = caps.get(0).unwrap();
+        self.decode_others(&header[matched.end()..]);
+
+        Ok((matched.start(), matched.end()))
+    }
+
+    // Keeps `text` and splits it into `others`
+    fn decode_others(&mut self, text: &str) {
+        self.text = text.trim_end().to_string();
+        self.others = Self::split_others(text);
+    }
+
+    // `key=value` pairs, other words as the description
+    fn split_others(text: &str) -> BTreeMap<String, String> {
+        let mut others = BTreeMap::new();
+        let mut words: Vec<&str> = vec![];
+        for word in text.split_whitespace() {
+            match word.find('=') {
+                Some(idx) if idx > 0 => {
+                    others.insert(word[..idx].to_string(), word[idx + 1..].to_string());
+                }
+                _ => words.push(word),
+            }
+        }
+
+        if !words.is_empty() {
+            others.insert("".to_string(), words.join(" "));
+        }
+        others
     }
 
     fn encode(&self) -> String {
@@ -210,7 +566,7 @@ impl Range {
             header += ")";
         }
 
-        if self.start != 0 {
+        if self.start != T::ZERO {
             header += ":";
             header += self.start.to_string().as_str();
             if self.end != self.start {
@@ -219,15 +575,314 @@ impl Range {
             }
         }
 
+        // the text as read, unless `others` has been changed since
+        if !self.text.is_empty() && Self::split_others(&self.text) == self.others {
+            header += self.text.as_str();
+            return header;
+        }
+
+        // attributes first, then the description
+        for (key, value) in self.others.iter().filter(|(k, _)| !k.is_empty()) {
+            header += " ";
+            header += key.as_str();
+            header += "=";
+            header += value.as_str();
+        }
+        if let Some(description) = self.description() {
+            header += " ";
+            header += description.as_str();
+        }
+
         header
     }
 }
 
+/// To string
+///
+/// ```
+/// # use intspan::Range;
+/// let range = Range::from("I", 1, 100);
+/// assert_eq!(range.to_string(), "I:1-100");
+/// let range = Range::from("I", 100, 100);
+/// assert_eq!(range.to_string(), "I:100");
+/// ```
+impl<T: Coord> fmt::Display for GenericRange<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+/// Builder returned by `Range::builder()`
+pub struct GenericRangeBuilder<T> {
+    range: GenericRange<T>,
+}
+
+/// `RangeBuilder` with `i32` coordinates
+pub type RangeBuilder = GenericRangeBuilder<i32>;
+
+impl<T: Coord> GenericRangeBuilder<T> {
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.range.name = name.into();
+        self
+    }
+
+    pub fn chr<S: Into<String>>(mut self, chr: S) -> Self {
+        self.range.chr = chr.into();
+        self
+    }
+
+    pub fn strand<S: Into<String>>(mut self, strand: S) -> Self {
+        self.range.strand = strand.into();
+        self
+    }
+
+    pub fn start(mut self, start: T) -> Self {
+        self.range.start = start;
+        self
+    }
+
+    /// Defaults to the start
+    pub fn end(mut self, end: T) -> Self {
+        self.range.end = end;
+        self
+    }
+
+    /// A `key=value` attribute, or the description with an empty `key`
+    pub fn other<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.range.others.insert(key.into(), value.into());
+        self
+    }
+
+    /// Checked as by `Range::parse()`
+    pub fn build(mut self) -> Result<GenericRange<T>, RangeError> {
+        if self.range.end == T::ZERO {
+            self.range.end = self.range.start;
+        }
+        self.range.validate(&self.range.to_string())?;
+
+        Ok(self.range)
+    }
+}
+
+/// The kind of error encountered while parsing a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeErrorKind {
+    /// Nothing like `chr:start-end`, e.g. a bare chromosome name or a comment
+    NoCoordinates,
+    /// Something after `:` other than positive integers
+    BadCoordinates,
+    /// The end is before the start
+    Inverted,
+    /// A strand other than `+` and `-`
+    BadStrand,
+    /// A coordinate beyond the largest element of an `IntSpan` of the coordinate type, see
+    /// `IntSpan::get_pos_inf()`
+    Overflow,
+}
+
+/// Error returned by `Range::parse()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeError {
+    kind: RangeErrorKind,
+    range: String,
+}
+
+impl RangeError {
+    fn new(kind: RangeErrorKind, range: &str) -> Self {
+        Self {
+            kind,
+            range: range.to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> RangeErrorKind {
+        self.kind
+    }
+
+    /// The offending text
+    pub fn range(&self) -> &str {
+        &self.range
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self.kind {
+            RangeErrorKind::NoCoordinates => "No coordinates",
+            RangeErrorKind::BadCoordinates => "Invalid coordinates",
+            RangeErrorKind::Inverted => "Inverted coordinates",
+            RangeErrorKind::BadStrand => "Invalid strand",
+            RangeErrorKind::Overflow => "Coordinate overflow",
+        };
+        write!(f, "{}: {}", message, self.range)
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// Equal in all fields, `others` compared by contents, regardless of how it was written
+impl<T: Coord> PartialEq for GenericRange<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Coord> Eq for GenericRange<T> {}
+
+impl<T: Coord> Hash for GenericRange<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.chr.hash(state);
+        self.strand.hash(state);
+        self.start.hash(state);
+        self.end.hash(state);
+        self.others.hash(state);
+    }
+}
+
+/// Ordered by chromosomes in natural order, see `natural_cmp()`, then by start, end, strand and
+/// name. Attributes only break the remaining ties. For other orders of chromosomes, see
+/// `ChrOrder::compare_ranges()`
+///
+/// ```
+/// # use intspan::Range;
+/// let mut ranges: Vec<Range> = ["chr10:1-10", "chr2:5-10", "chr2(-):1-10", "chr2(+):1-10", "chr2:1-5"]
+///     .iter()
+///     .map(|s| Range::from_str(*s))
+///     .collect();
+/// ranges.sort();
+/// let ranges: Vec<String> = ranges.iter().map(|r| r.to_string()).collect();
+/// assert_eq!(
+///     ranges,
+///     vec!["chr2:1-5", "chr2(+):1-10", "chr2(-):1-10", "chr2:5-10", "chr10:1-10"]
+/// );
+/// ```
+impl<T: Coord> Ord for GenericRange<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        natural_cmp(&self.chr, &other.chr)
+            .then_with(|| self.start.cmp(&other.start))
+            .then_with(|| self.end.cmp(&other.end))
+            .then_with(|| self.strand.cmp(&other.strand))
+            .then_with(|| self.name.cmp(&other.name))
+            .then_with(|| self.others.cmp(&other.others))
+    }
+}
+
+impl<T: Coord> PartialOrd for GenericRange<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[test]
+fn range64() {
+    let range = Range64::from_str("I:1-3000000000");
+    assert_eq!(*range.end(), 3_000_000_000);
+    assert_eq!(range.intspan().cardinality(), 3_000_000_000);
+    assert_eq!(range.to_string(), "I:1-3000000000");
+}
+
+#[test]
+fn strand_aware() {
+    // range upstream downstream expSlop expUp expDown
+    let tests = vec![
+        ("I:101-200", 10, 20, "I:91-220", "I:91-100", "I:201-220"),
+        (
+            "I(+):101-200",
+            10,
+            20,
+            "I(+):91-220",
+            "I(+):91-100",
+            "I(+):201-220",
+        ),
+        (
+            "I(-):101-200",
+            10,
+            20,
+            "I(-):81-210",
+            "I(-):201-210",
+            "I(-):81-100",
+        ),
+        ("I(-):101-200", 200, 0, "I(-):101-400", "I(-):201-400", ""),
+        ("I(-):101-200", 0, 200, "I(-):1-200", "", "I(-):1-100"),
+        (
+            "S288c.I(+):5",
+            10,
+            1,
+            "S288c.I(+):1-6",
+            "S288c.I(+):1-4",
+            "S288c.I(+):6",
+        ),
+        ("I:1-10", -50, -5, "I:1-10", "", ""),
+        ("I(-):101-110", -50, 5, "I(-):96-110", "", "I(-):96-100"),
+        (
+            "I:2147483646",
+            1,
+            5,
+            "I:2147483645-2147483647",
+            "I:2147483645",
+            "I:2147483647",
+        ),
+        ("I:2147483647", 0, 5, "I:2147483647", "", ""),
+    ];
+    for (range, upstream, downstream, exp_slop, exp_up, exp_down) in tests {
+        let range = Range::from_str(range);
+        assert_eq!(range.slop(upstream, downstream).to_string(), exp_slop);
+
+        let (up, down) = range.flank(upstream, downstream);
+        assert_eq!(up.map(|r| r.to_string()).unwrap_or_default(), exp_up);
+        assert_eq!(down.map(|r| r.to_string()).unwrap_or_default(), exp_down);
+    }
+}
+
+#[test]
+fn parse_errors() {
+    let tests = vec![
+        ("", RangeErrorKind::NoCoordinates),
+        ("S288c The baker's yeast", RangeErrorKind::NoCoordinates),
+        ("1:-100", RangeErrorKind::BadCoordinates),
+        ("I:0", RangeErrorKind::BadCoordinates),
+        ("I(.):1-10", RangeErrorKind::BadStrand),
+        ("I:10-1", RangeErrorKind::Inverted),
+        ("I:99999999999", RangeErrorKind::Overflow),
+        ("I:1-2147483647", RangeErrorKind::Overflow),
+        ("I:2147483646", RangeErrorKind::Overflow),
+        ("chr1:1,000-2,000", RangeErrorKind::BadCoordinates),
+        ("junk I:1-10", RangeErrorKind::BadCoordinates),
+        ("I:1x10", RangeErrorKind::BadCoordinates),
+        ("I:1-10-20", RangeErrorKind::BadCoordinates),
+        ("I:1-", RangeErrorKind::BadCoordinates),
+    ];
+    for (range, kind) in tests {
+        let err = Range::parse(range).err().unwrap();
+        assert_eq!(err.kind(), kind, "{}", range);
+        assert_eq!(err.range(), range);
+    }
+
+    assert_eq!(
+        *Range64::parse("I:99999999999").unwrap().end(),
+        99_999_999_999
+    );
+    assert_eq!(Range::parse("I(+):5").unwrap().to_string(), "I(+):5");
+    assert_eq!(
+        Range::parse("I:1-10\tgene=x").unwrap().to_string(),
+        "I:1-10\tgene=x"
+    );
+    assert!(Range::parse("S288c.I(-):190-200|Species=Yeast").is_ok());
+}
+
 #[test]
 fn fa_headers() {
     let tests = vec![
         ("S288c", "S288c"),
-        ("S288c The baker's yeast", "S288c"),
+        ("S288c The baker's yeast", "S288c The baker's yeast"),
+        ("S288c  The baker's\tyeast", "S288c  The baker's\tyeast"),
+        ("I:1-100 b=2 a=1 some text", "I:1-100 b=2 a=1 some text"),
+        ("I:1-100 x note=1 a=b=c a=2 ", "I:1-100 x note=1 a=b=c a=2"),
+        (
+            "S288c.I(-):190-200|Species=Yeast",
+            "S288c.I(-):190-200|Species=Yeast",
+        ),
         ("1:-100", "1:-100"),
         ("infile_0/1/0_514:19-25", "infile_0/1/0_514:19-25"),
     ];
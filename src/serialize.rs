@@ -0,0 +1,196 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! `IntSpan` is (de)serialized as its runlist and `Range` as its string form, so both read naturally
+//! in YAML and JSON.
+//!
+//! ```
+//! # use intspan::{IntSpan, Range};
+//! let set = IntSpan::from("1-3,5");
+//! assert_eq!(serde_json::to_string(&set).unwrap(), r#""1-3,5""#);
+//!
+//! let range: Range = serde_json::from_str(r#""S288c.I(-):27070-29557""#).unwrap();
+//! assert_eq!(*range.start(), 27070);
+//! ```
+//!
+//! Use `as_spans` for the `[[lower, upper], ...]` form instead:
+//!
+//! ```
+//! # use intspan::IntSpan;
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Feature {
+//!     #[serde(with = "intspan::as_spans")]
+//!     exons: IntSpan,
+//! }
+//!
+//! let feature = Feature { exons: IntSpan::from("1-3,5") };
+//! assert_eq!(
+//!     serde_json::to_string(&feature).unwrap(),
+//!     r#"{"exons":[[1,3],[5,5]]}"#
+//! );
+//! ```
+
+use crate::{Coord, GenericIntSpan, GenericRange};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+
+impl<T: Coord> Serialize for GenericIntSpan<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: Coord> Deserialize<'de> for GenericIntSpan<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(RunlistVisitor(PhantomData))
+    }
+}
+
+struct RunlistVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Coord> Visitor<'de> for RunlistVisitor<T> {
+    type Value = GenericIntSpan<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a runlist like 1-3,5")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        GenericIntSpan::try_from(v).map_err(E::custom)
+    }
+}
+
+impl<T: Coord> Serialize for GenericRange<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, T: Coord> Deserialize<'de> for GenericRange<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(RangeVisitor(PhantomData))
+    }
+}
+
+struct RangeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Coord> Visitor<'de> for RangeVisitor<T> {
+    type Value = GenericRange<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a range like I(+):1-100")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        GenericRange::parse(v).map_err(E::custom)
+    }
+}
+
+/// (De)serializes an `IntSpan` as a sequence of `[lower, upper]` pairs, for `#[serde(with = "...")]`
+pub mod as_spans {
+    use crate::{Coord, GenericIntSpan};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(set: &GenericIntSpan<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Coord + serde::Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(set.spans())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<GenericIntSpan<T>, D::Error>
+    where
+        T: Coord + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let spans: Vec<(T, T)> = Vec::deserialize(deserializer)?;
+        for &(lower, upper) in &spans {
+            if lower > upper {
+                return Err(serde::de::Error::custom(format!(
+                    "Bad order: {},{}",
+                    lower, upper
+                )));
+            }
+        }
+
+        Ok(spans.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{as_spans, IntSpan, IntSpan64, Range};
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize)]
+    struct Record {
+        range: Range,
+        set: IntSpan,
+        #[serde(with = "as_spans")]
+        spans: IntSpan64,
+    }
+
+    fn assert_record(rec: &Record) {
+        assert_eq!(rec.range.to_string(), "S288c.I(-):27070-29557");
+        assert_eq!(rec.set.to_string(), "1-3,5");
+        assert_eq!(rec.spans.to_string(), "1-3000000000,4000000000");
+    }
+
+    fn record() -> Record {
+        Record {
+            range: Range::from_str("S288c.I(-):27070-29557"),
+            set: IntSpan::from("1-3,5"),
+            spans: IntSpan64::from("1-3000000000,4000000000"),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let rec = record();
+
+        let json = serde_json::to_string(&rec).unwrap();
+        assert_eq!(
+            json,
+            r#"{"range":"S288c.I(-):27070-29557","set":"1-3,5","spans":[[1,3000000000],[4000000000,4000000000]]}"#
+        );
+        assert_record(&serde_json::from_str::<Record>(&json).unwrap());
+
+        let yaml = serde_yaml::to_string(&rec).unwrap();
+        assert_record(&serde_yaml::from_str::<Record>(&yaml).unwrap());
+
+        let bin = bincode::serialize(&rec).unwrap();
+        assert_record(&bincode::deserialize::<Record>(&bin).unwrap());
+    }
+
+    #[test]
+    fn runlist_yaml() {
+        let set_of: BTreeMap<String, IntSpan> =
+            serde_yaml::from_str("I: 1-100\nII: '-'\n").unwrap();
+        assert_eq!(set_of.get("I").unwrap().cardinality(), 100);
+        assert!(set_of.get("II").unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = serde_json::from_str::<IntSpan>(r#""1-3,x""#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Number format error: x at 4 of 1-3,x"));
+
+        let err = serde_json::from_str::<Record>(r#"{"range":"I:1","set":"1","spans":[[5,3]]}"#)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Bad order: 5,3"));
+
+        let err = serde_json::from_str::<Range>(r#""garbage here""#).unwrap_err();
+        assert!(err.to_string().contains("No coordinates: garbage here"));
+
+        let err = serde_json::from_str::<Range>(r#""I:500-100""#).unwrap_err();
+        assert!(err.to_string().contains("Inverted coordinates: I:500-100"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Similarity metrics between sets, or between chromosome maps of sets.
+//!
+//! ```
+//! use intspan::{IntSpan, Similarity};
+//! use std::collections::BTreeMap;
+//!
+//! let mut a: BTreeMap<String, IntSpan> = BTreeMap::new();
+//! a.insert("I".to_string(), IntSpan::from("1-100"));
+//! a.insert("II".to_string(), IntSpan::from("1-50"));
+//! let mut b: BTreeMap<String, IntSpan> = BTreeMap::new();
+//! b.insert("I".to_string(), IntSpan::from("51-150"));
+//!
+//! let sim = Similarity::of_map(&a, &b);
+//! assert_eq!(sim.intersect(), 50);
+//! assert_eq!(sim.union(), 200);
+//! assert_eq!(sim.jaccard(), 0.25);
+//! assert_eq!(sim.bp_distance(), 150);
+//! ```
+
+use crate::{Coord, GenericIntSpan};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Sizes of two sets and of their intersection, from which all metrics follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Similarity {
+    size_a: i128,
+    size_b: i128,
+    intersect: i128,
+}
+
+impl Similarity {
+    pub fn of<T: Coord>(a: &GenericIntSpan<T>, b: &GenericIntSpan<T>) -> Self {
+        Self {
+            size_a: a.cardinality().to_i128(),
+            size_b: b.cardinality().to_i128(),
+            intersect: a.overlap(b).to_i128(),
+        }
+    }
+
+    /// Summed over chromosomes. A chromosome missing on one side counts as empty there
+    pub fn of_map<T: Coord>(
+        a: &BTreeMap<String, GenericIntSpan<T>>,
+        b: &BTreeMap<String, GenericIntSpan<T>>,
+    ) -> Self {
+        let chrs: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+
+        let empty = GenericIntSpan::new();
+        let mut sim = Self::default();
+        for chr in chrs {
+            let one = Self::of(a.get(chr).unwrap_or(&empty), b.get(chr).unwrap_or(&empty));
+            sim.size_a += one.size_a;
+            sim.size_b += one.size_b;
+            sim.intersect += one.intersect;
+        }
+
+        sim
+    }
+
+    pub fn size_a(&self) -> i128 {
+        self.size_a
+    }
+
+    pub fn size_b(&self) -> i128 {
+        self.size_b
+    }
+
+    pub fn intersect(&self) -> i128 {
+        self.intersect
+    }
+
+    pub fn union(&self) -> i128 {
+        self.size_a + self.size_b - self.intersect
+    }
+
+    /// `|A ∩ B| / |A ∪ B|`, 0 for two empty sets
+    pub fn jaccard(&self) -> f64 {
+        ratio(self.intersect, self.union())
+    }
+
+    /// Sørensen–Dice coefficient, `2|A ∩ B| / (|A| + |B|)`, 0 for two empty sets
+    pub fn dice(&self) -> f64 {
+        ratio(2 * self.intersect, self.size_a + self.size_b)
+    }
+
+    /// `|A ∩ B| / min(|A|, |B|)`, 0 if either set is empty
+    pub fn overlap_coefficient(&self) -> f64 {
+        ratio(self.intersect, self.size_a.min(self.size_b))
+    }
+
+    /// Integers in exactly one of the sets, `|A ∪ B| - |A ∩ B|`
+    pub fn bp_distance(&self) -> i128 {
+        self.union() - self.intersect
+    }
+}
+
+fn ratio(numerator: i128, denominator: i128) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntSpan;
+
+    #[test]
+    fn test_metrics() {
+        // A B jaccard dice overlap_coefficient bp_distance
+        let tests = vec![
+            ("-", "-", 0.0, 0.0, 0.0, 0),
+            ("1-10", "-", 0.0, 0.0, 0.0, 10),
+            ("1-10", "1-10", 1.0, 1.0, 1.0, 0),
+            ("1-10", "11-20", 0.0, 0.0, 0.0, 20),
+            ("1-10", "6-15", 5.0 / 15.0, 0.5, 0.5, 10),
+            ("1-100", "41-60", 0.2, 40.0 / 120.0, 1.0, 80),
+            (
+                "1-10,21-30",
+                "5-25",
+                11.0 / 30.0,
+                22.0 / 41.0,
+                11.0 / 20.0,
+                19,
+            ),
+        ];
+
+        for (a, b, jaccard, dice, coefficient, distance) in tests {
+            let sim = Similarity::of(&IntSpan::from(a), &IntSpan::from(b));
+            assert!(
+                (sim.jaccard() - jaccard).abs() < 1e-9,
+                "jaccard {} {}",
+                a,
+                b
+            );
+            assert!((sim.dice() - dice).abs() < 1e-9, "dice {} {}", a, b);
+            assert!(
+                (sim.overlap_coefficient() - coefficient).abs() < 1e-9,
+                "overlap coefficient {} {}",
+                a,
+                b
+            );
+            assert_eq!(sim.bp_distance(), distance, "bp distance {} {}", a, b);
+
+            // symmetric
+            let rev = Similarity::of(&IntSpan::from(b), &IntSpan::from(a));
+            assert_eq!(sim.jaccard(), rev.jaccard());
+            assert_eq!(sim.bp_distance(), rev.bp_distance());
+        }
+    }
+}
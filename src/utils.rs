@@ -1,96 +1,229 @@
-use crate::{IntSpan, Range};
+use crate::{
+    bin2set_m, is_bin, set2bin, ChrOrder, Coord, Error, GenericIntSpan, Range, RangeErrorKind,
+    Result,
+};
 use serde_yaml::Value;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::TryFrom;
 use std::fs;
 use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
 
 /// ```
 /// use std::io::BufRead;
-/// let reader = intspan::reader("tests/resources/S288c.chr.sizes");
+/// let reader = intspan::reader("tests/resources/S288c.chr.sizes").unwrap();
 /// let mut lines = vec![];
 /// for line in reader.lines() {
 ///     lines.push(line);
 /// }
 /// assert_eq!(lines.len(), 16);
 ///
-/// let reader = intspan::reader("tests/resources/S288c.chr.sizes");
+/// let reader = intspan::reader("tests/resources/S288c.chr.sizes").unwrap();
 /// assert_eq!(reader.lines().collect::<Vec<_>>().len(), 16);
 /// ```
-pub fn reader(input: &str) -> Box<dyn BufRead> {
+pub fn reader(input: &str) -> Result<Box<dyn BufRead>> {
     let reader: Box<dyn BufRead> = if input == "stdin" {
         Box::new(BufReader::new(io::stdin()))
     } else {
-        Box::new(BufReader::new(fs::File::open(input).unwrap()))
+        let file = fs::File::open(input).map_err(|e| Error::io(input, e))?;
+        Box::new(BufReader::new(file))
     };
 
-    reader
+    Ok(reader)
 }
 
 /// ```
-/// let lines = intspan::read_lines("tests/resources/S288c.chr.sizes");
+/// let lines = intspan::read_lines("tests/resources/S288c.chr.sizes").unwrap();
 /// assert_eq!(lines.len(), 16);
 /// ```
-pub fn read_lines(input: &str) -> Vec<String> {
-    let mut reader = reader(input);
+pub fn read_lines(input: &str) -> Result<Vec<String>> {
+    let mut reader = reader(input)?;
     let mut s = String::new();
-    reader.read_to_string(&mut s);
-    s.lines().map(|s| s.to_string()).collect::<Vec<String>>()
+    reader
+        .read_to_string(&mut s)
+        .map_err(|e| Error::io(input, e))?;
+    Ok(s.lines().map(|s| s.to_string()).collect::<Vec<String>>())
 }
 
+/// Lines other than two tab-separated fields are skipped
+///
 /// ```
-/// let sizes = intspan::read_sizes("tests/resources/S288c.chr.sizes");
+/// let sizes = intspan::read_sizes("tests/resources/S288c.chr.sizes").unwrap();
 /// assert_eq!(sizes.len(), 16);
 /// assert_eq!(*sizes.get("II").unwrap(), 813184);
 /// ```
-pub fn read_sizes(input: &str) -> BTreeMap<String, i32> {
+pub fn read_sizes(input: &str) -> Result<BTreeMap<String, i32>> {
     let mut sizes: BTreeMap<String, i32> = BTreeMap::new();
 
-    for line in read_lines(input) {
+    for (i, line) in read_lines(input)?.iter().enumerate() {
         let fields: Vec<&str> = line.split('\t').collect();
         if fields.len() == 2 {
-            sizes.insert(fields[0].to_string(), fields[1].parse::<i32>().unwrap());
+            let size = fields[1].parse::<i32>().map_err(|e| {
+                Error::parse(input, i + 1, format!("Invalid size {}: {}", fields[1], e))
+            })?;
+            sizes.insert(fields[0].to_string(), size);
         }
     }
 
-    sizes
+    Ok(sizes)
 }
 
-pub fn read_yaml(input: &str) -> BTreeMap<String, Value> {
-    let mut reader = reader(input);
-    let mut s = String::new();
-    reader.read_to_string(&mut s);
+/// Binary runlists are detected by their magic bytes and converted to the YAML form
+///
+/// ```
+/// let yaml = intspan::read_yaml("tests/resources/I.II.yml").unwrap();
+/// assert_eq!(yaml.len(), 2);
+/// ```
+pub fn read_yaml(input: &str) -> Result<BTreeMap<String, Value>> {
+    let bytes = read_bytes(input)?;
+
+    let yaml = if is_bin(&bytes) {
+        let set_of: BTreeMap<String, BTreeMap<String, GenericIntSpan<i64>>> =
+            bin2set_m(&bytes).map_err(|e| Error::from(e).in_file(input))?;
+        match set_of.get("__single") {
+            Some(set) => set2yaml(set),
+            None => set2yaml_m(&set_of),
+        }
+    } else {
+        serde_yaml::from_slice(&bytes).map_err(|e| Error::from(e).in_file(input))?
+    };
 
-    serde_yaml::from_str(&s).unwrap()
+    Ok(yaml)
 }
 
-pub fn writer(output: &str) -> Box<dyn Write> {
+/// Loads a runlist file, YAML or binary, as in `yaml2set_m()`
+///
+/// ```
+/// # use intspan::IntSpan;
+/// # use std::collections::BTreeMap;
+/// let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+///     intspan::read_set_m("tests/resources/I.II.yml").unwrap();
+/// assert!(set_of["II"]["II"].contains(21294));
+/// ```
+pub fn read_set_m<T: Coord>(
+    input: &str,
+) -> Result<BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>> {
+    let bytes = read_bytes(input)?;
+
+    let set_of = if is_bin(&bytes) {
+        bin2set_m(&bytes).map_err(Error::from)
+    } else {
+        serde_yaml::from_slice(&bytes)
+            .map_err(Error::from)
+            .and_then(|yaml| yaml2set_m(&yaml))
+    };
+
+    set_of.map_err(|e| e.in_file(input))
+}
+
+/// Loads a single-name runlist file, YAML or binary, as in `yaml2set()`
+///
+/// ```
+/// let err = intspan::read_set::<i32>("tests/resources/Atha.yml").err().unwrap();
+/// assert_eq!(
+///     err.to_string(),
+///     "tests/resources/Atha.yml: Not a single-name runlist file"
+/// );
+/// ```
+pub fn read_set<T: Coord>(input: &str) -> Result<BTreeMap<String, GenericIntSpan<T>>> {
+    let mut set_of = read_set_m(input)?;
+
+    set_of
+        .remove("__single")
+        .ok_or_else(|| Error::parse(input, 0, "Not a single-name runlist file"))
+}
+
+/// Loads a runlist file as labelled sets, the names of a multi-name file or the file stem of a
+/// single-name one
+///
+/// ```
+/// # use intspan::IntSpan;
+/// let sets = intspan::read_set_labelled::<i32>("tests/resources/intergenic.yml").unwrap();
+/// assert!(sets.contains_key("intergenic"));
+///
+/// let sets = intspan::read_set_labelled::<i32>("tests/resources/Atha.yml").unwrap();
+/// assert_eq!(sets.len(), 5);
+/// assert!(sets.contains_key("AT1G01010.1"));
+/// ```
+pub fn read_set_labelled<T: Coord>(
+    input: &str,
+) -> Result<BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>> {
+    let sets = read_set_m(input)?
+        .into_iter()
+        .map(|(name, set)| {
+            let label = if name == "__single" {
+                Path::new(input)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| input.to_string())
+            } else {
+                name
+            };
+            (label, set)
+        })
+        .collect();
+
+    Ok(sets)
+}
+
+fn read_bytes(input: &str) -> Result<Vec<u8>> {
+    let mut reader = reader(input)?;
+    let mut bytes = vec![];
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| Error::io(input, e))?;
+
+    Ok(bytes)
+}
+
+pub fn writer(output: &str) -> Result<Box<dyn Write>> {
     let writer: Box<dyn Write> = if output == "stdout" {
         Box::new(BufWriter::new(io::stdout()))
     } else {
-        Box::new(BufWriter::new(fs::File::create(output).unwrap()))
+        let file = fs::File::create(output).map_err(|e| Error::io(output, e))?;
+        Box::new(BufWriter::new(file))
     };
 
-    writer
+    Ok(writer)
 }
 
-pub fn write_lines(output: &str, lines: &Vec<&str>) -> Result<(), Box<dyn std::error::Error>> {
-    let mut writer = writer(output);
+pub fn write_lines(output: &str, lines: &Vec<&str>) -> Result<()> {
+    let mut writer = writer(output)?;
 
     for line in lines {
-        writer.write_all(format!("{}\n", line).as_ref())?;
+        writer
+            .write_all(format!("{}\n", line).as_ref())
+            .map_err(|e| Error::io(output, e))?;
     }
 
     Ok(())
 }
 
-pub fn write_yaml(
+pub fn write_yaml(output: &str, yaml: &BTreeMap<String, Value>) -> Result<()> {
+    write_yaml_in(output, yaml, &ChrOrder::Lexical)
+}
+
+/// Writes YAML with chromosomes in `order`, see `ChrOrder::yaml()`
+pub fn write_yaml_in(output: &str, yaml: &BTreeMap<String, Value>, order: &ChrOrder) -> Result<()> {
+    let mut writer = writer(output)?;
+    let mut s = serde_yaml::to_string(&order.yaml(yaml))?;
+    s.push('\n');
+    writer
+        .write_all(s.as_bytes())
+        .map_err(|e| Error::io(output, e))?;
+
+    Ok(())
+}
+
+/// Writes sets in the binary container, see `set2bin()`
+pub fn write_bin<T: Coord>(
     output: &str,
-    yaml: &BTreeMap<String, Value>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut writer = writer(output);
-    let mut s = serde_yaml::to_string(yaml).unwrap();
-    s.push_str("\n");
-    writer.write_all(s.as_bytes())?;
+    set_of: &BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
+) -> Result<()> {
+    let mut writer = writer(output)?;
+    writer
+        .write_all(&set2bin(set_of))
+        .map_err(|e| Error::io(output, e))?;
 
     Ok(())
 }
@@ -102,18 +235,28 @@ pub fn write_yaml(
 /// let mut runlists: BTreeMap<String, Value> = BTreeMap::new();
 /// runlists.insert("I".to_string(), value);
 ///
-/// let sets = intspan::yaml2set(&runlists);
+/// let sets: BTreeMap<String, intspan::IntSpan> = intspan::yaml2set(&runlists).unwrap();
 /// assert!(sets.values().next().unwrap().contains(28550));
+///
+/// runlists.insert("II".to_string(), Value::Bool(true));
+/// let err = intspan::yaml2set::<i32>(&runlists).err().unwrap();
+/// assert_eq!(err.to_string(), "Runlist of II is not a string");
 /// ```
-pub fn yaml2set(yaml: &BTreeMap<String, Value>) -> BTreeMap<String, IntSpan> {
-    let mut set: BTreeMap<String, IntSpan> = BTreeMap::new();
+pub fn yaml2set<T: Coord>(
+    yaml: &BTreeMap<String, Value>,
+) -> Result<BTreeMap<String, GenericIntSpan<T>>> {
+    let mut set: BTreeMap<String, GenericIntSpan<T>> = BTreeMap::new();
 
     for (chr, value) in yaml {
-        let intspan = IntSpan::from(value.as_str().unwrap());
+        let runlist = value
+            .as_str()
+            .ok_or_else(|| Error::parse("", 0, format!("Runlist of {} is not a string", chr)))?;
+        let intspan = GenericIntSpan::try_from(runlist)
+            .map_err(|e| Error::parse("", 0, format!("Runlist of {}: {}", chr, e)))?;
         set.insert(chr.into(), intspan);
     }
 
-    set
+    Ok(set)
 }
 
 /// ```
@@ -131,7 +274,7 @@ pub fn yaml2set(yaml: &BTreeMap<String, Value>) -> BTreeMap<String, IntSpan> {
 ///     &Value::String("28547-29194".into())
 /// );
 /// ```
-pub fn set2yaml(set: &BTreeMap<String, IntSpan>) -> BTreeMap<String, Value> {
+pub fn set2yaml<T: Coord>(set: &BTreeMap<String, GenericIntSpan<T>>) -> BTreeMap<String, Value> {
     let mut yaml: BTreeMap<String, Value> = BTreeMap::new();
 
     for (chr, value) in set {
@@ -142,7 +285,9 @@ pub fn set2yaml(set: &BTreeMap<String, IntSpan>) -> BTreeMap<String, Value> {
     yaml
 }
 
-pub fn set2yaml_m(set_of: &BTreeMap<String, BTreeMap<String, IntSpan>>) -> BTreeMap<String, Value> {
+pub fn set2yaml_m<T: Coord>(
+    set_of: &BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
+) -> BTreeMap<String, Value> {
     let mut out_yaml: BTreeMap<String, Value> = BTreeMap::new();
 
     for (name, set) in set_of {
@@ -153,48 +298,53 @@ pub fn set2yaml_m(set_of: &BTreeMap<String, BTreeMap<String, IntSpan>>) -> BTree
     out_yaml
 }
 
-pub fn yaml2set_m(yaml: &BTreeMap<String, Value>) -> BTreeMap<String, BTreeMap<String, IntSpan>> {
-    let is_multi: bool = yaml.values().next().unwrap().is_mapping();
+pub fn yaml2set_m<T: Coord>(
+    yaml: &BTreeMap<String, Value>,
+) -> Result<BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>> {
+    let is_multi: bool = yaml.values().next().is_some_and(|v| v.is_mapping());
 
-    let mut s_of: BTreeMap<String, BTreeMap<String, IntSpan>> = BTreeMap::new();
+    let mut s_of: BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>> = BTreeMap::new();
     if is_multi {
         for (key, value) in yaml {
-            let string = serde_yaml::to_string(value).unwrap();
-            let runlist_one: BTreeMap<String, Value> =
-                serde_yaml::from_str(string.as_str()).unwrap();
-            let set_one = yaml2set(&runlist_one);
+            // through text, so that numeric chromosomes become strings
+            let string = serde_yaml::to_string(value)?;
+            let runlist_one: BTreeMap<String, Value> = serde_yaml::from_str(string.as_str())
+                .map_err(|_| Error::parse("", 0, format!("{} is not a set of runlists", key)))?;
+            let set_one = yaml2set(&runlist_one)?;
             s_of.insert(key.to_string(), set_one);
         }
     } else {
-        let set_one = yaml2set(&yaml);
+        let set_one = yaml2set(yaml)?;
         s_of.insert("__single".to_string(), set_one);
     }
 
-    s_of
+    Ok(s_of)
 }
 
-pub fn fill_up_m(
-    set_of: &mut BTreeMap<String, BTreeMap<String, IntSpan>>,
+pub fn fill_up_m<T: Coord>(
+    set_of: &mut BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
     chrs: &BTreeSet<String>,
 ) {
     for set in set_of.values_mut() {
         for chr in chrs {
             if !set.contains_key(chr) {
-                set.insert(chr.into(), IntSpan::new());
+                set.insert(chr.into(), GenericIntSpan::new());
             }
         }
     }
 }
 
-pub fn fill_up_s(set: &mut BTreeMap<String, IntSpan>, chrs: &BTreeSet<String>) {
+pub fn fill_up_s<T: Coord>(set: &mut BTreeMap<String, GenericIntSpan<T>>, chrs: &BTreeSet<String>) {
     for chr in chrs {
         if !set.contains_key(chr) {
-            set.insert(chr.into(), IntSpan::new());
+            set.insert(chr.into(), GenericIntSpan::new());
         }
     }
 }
 
-pub fn chrs_in_sets(set_of: &BTreeMap<String, BTreeMap<String, IntSpan>>) -> BTreeSet<String> {
+pub fn chrs_in_sets<T: Coord>(
+    set_of: &BTreeMap<String, BTreeMap<String, GenericIntSpan<T>>>,
+) -> BTreeSet<String> {
     let mut chrs: BTreeSet<String> = BTreeSet::new();
 
     for name in set_of.keys() {
@@ -206,20 +356,48 @@ pub fn chrs_in_sets(set_of: &BTreeMap<String, BTreeMap<String, IntSpan>>) -> BTr
     chrs
 }
 
-pub fn build_range_of_part(line: &String, range_of_str: &mut HashMap<String, Range>) {
+/// Range in a part of line `line` of `file`. Parts without coordinates, like headers or other
+/// columns, are `None`. Malformed ranges are skipped with a warning, or errors with `strict`
+///
+/// ```
+/// let range = intspan::parse_range("I:1-100", "file", 1, true).unwrap();
+/// assert_eq!(range.unwrap().to_string(), "I:1-100");
+/// assert!(intspan::parse_range("#comment", "file", 2, true).unwrap().is_none());
+/// assert!(intspan::parse_range("I:500-100", "file", 3, false).unwrap().is_none());
+///
+/// let err = intspan::parse_range("I:500-100", "file", 3, true).err().unwrap();
+/// assert_eq!(err.to_string(), "file:3: Inverted coordinates: I:500-100");
+/// ```
+pub fn parse_range(part: &str, file: &str, line: usize, strict: bool) -> Result<Option<Range>> {
+    match Range::parse(part) {
+        Ok(range) => Ok(Some(range)),
+        Err(err) if err.kind() == RangeErrorKind::NoCoordinates => Ok(None),
+        Err(err) => {
+            let err = Error::parse(file, line, err.to_string());
+            if strict {
+                Err(err)
+            } else {
+                eprintln!("Skipped {}", err);
+                Ok(None)
+            }
+        }
+    }
+}
+
+pub fn build_range_of_part(line: &str, range_of_str: &mut HashMap<String, Range>) {
     for part in line.split('\t') {
-        let range = Range::from_str(part);
-        if !range.is_valid() {
+        if range_of_str.contains_key(part) {
             continue;
         }
 
-        if !range_of_str.contains_key(part) {
+        if let Ok(range) = Range::parse(part) {
             range_of_str.insert(part.to_string(), range);
         }
     }
 }
 
-pub fn sort_links(lines: &Vec<String>) -> Vec<String> {
+/// Links sorted within and among them by their ranges, with chromosomes in `order`
+pub fn sort_links(lines: &Vec<String>, order: &ChrOrder) -> Vec<String> {
     // cache ranges
     let mut range_of_part: HashMap<String, Range> = HashMap::new();
 
@@ -244,14 +422,8 @@ pub fn sort_links(lines: &Vec<String>) -> Vec<String> {
             .filter(|p| !range_of_part.contains_key(*p))
             .collect();
 
-        // by chromosome strand
-        valids.sort_by_key(|k| range_of_part.get(*k).unwrap().strand());
-
-        // by start point on chromosomes
-        valids.sort_by_key(|k| range_of_part.get(*k).unwrap().start());
-
-        // by chromosome name
-        valids.sort_by_key(|k| range_of_part.get(*k).unwrap().chr());
+        // by chromosome name, start point and strand
+        valids.sort_by(|a, b| order.compare_ranges(&range_of_part[*a], &range_of_part[*b]));
 
         // recreate line
         valids.append(&mut invalids);
@@ -264,23 +436,9 @@ pub fn sort_links(lines: &Vec<String>) -> Vec<String> {
     //----------------------------
     let mut among_links: Vec<String> = within_links.into_iter().collect();
     {
-        // by chromosome strand
-        among_links.sort_by_cached_key(|k| {
-            let parts: Vec<&str> = k.split('\t').collect();
-            range_of_part.get(parts[0]).unwrap().strand()
-        });
-
-        // by start point on chromosomes
-        among_links.sort_by_cached_key(|k| {
-            let parts: Vec<&str> = k.split('\t').collect();
-            range_of_part.get(parts[0]).unwrap().start()
-        });
-
-        // by chromosome name
-        among_links.sort_by_cached_key(|k| {
-            let parts: Vec<&str> = k.split('\t').collect();
-            range_of_part.get(parts[0]).unwrap().chr()
-        });
+        // by chromosome name, start point and strand
+        let first = |k: &String| &range_of_part[k.split('\t').next().unwrap()];
+        among_links.sort_by(|a, b| order.compare_ranges(first(a), first(b)));
     }
 
     //----------------------------
@@ -299,6 +457,7 @@ pub fn sort_links(lines: &Vec<String>) -> Vec<String> {
 #[cfg(test)]
 mod read_write {
     use super::*;
+    use crate::IntSpan;
     use tempfile::TempDir;
 
     #[test]
@@ -310,9 +469,9 @@ mod read_write {
             .into_os_string()
             .into_string()
             .unwrap();
-        write_lines(&filename, &vec!["This", "is", "a\ntest"]);
+        write_lines(&filename, &vec!["This", "is", "a\ntest"]).unwrap();
 
-        let lines = read_lines(&filename);
+        let lines = read_lines(&filename).unwrap();
         assert_eq!(lines.len(), 4);
     }
 
@@ -326,12 +485,40 @@ mod read_write {
             .into_string()
             .unwrap();
 
-        let yaml = read_yaml("tests/resources/Atha.yml");
+        let yaml = read_yaml("tests/resources/Atha.yml").unwrap();
 
-        write_yaml(&filename, &yaml);
+        write_yaml(&filename, &yaml).unwrap();
 
-        let lines = read_lines(&filename);
+        let lines = read_lines(&filename).unwrap();
         assert_eq!(lines.len(), 11);
     }
 
+    #[test]
+    fn test_read_write_bin() {
+        let tmp = TempDir::new().unwrap();
+        let filename = tmp
+            .path()
+            .join("test.bin")
+            .into_os_string()
+            .into_string()
+            .unwrap();
+
+        let set_of: BTreeMap<String, BTreeMap<String, IntSpan>> =
+            read_set_m("tests/resources/Atha.yml").unwrap();
+        write_bin(&filename, &set_of).unwrap();
+
+        assert_eq!(read_set_m::<i32>(&filename).unwrap(), set_of);
+        assert_eq!(
+            yaml2set_m::<i32>(&read_yaml(&filename).unwrap()).unwrap(),
+            set_of
+        );
+
+        let set: BTreeMap<String, IntSpan> = read_set("tests/resources/intergenic.yml").unwrap();
+        write_bin(
+            &filename,
+            &read_set_m::<i32>("tests/resources/intergenic.yml").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(read_set::<i32>(&filename).unwrap(), set);
+    }
 }
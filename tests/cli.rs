@@ -97,6 +97,78 @@ fn command_merge() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn file_doesnt_exist_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("genome").arg("tests/file/doesnt/exist");
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::starts_with(
+            "intspan: tests/file/doesnt/exist: ",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+
+    Ok(())
+}
+
+#[test]
+fn file_unreadable() -> Result<(), Box<dyn std::error::Error>> {
+    for subcommand in &["cover", "gff", "flank"] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg(subcommand).arg("tests/resources");
+        cmd.assert()
+            .code(3)
+            .stderr(predicate::str::starts_with("intspan: tests/resources: "));
+    }
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("range")
+        .arg("tests/resources/intergenic.yml")
+        .arg("tests/resources");
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::starts_with("intspan: tests/resources: "));
+
+    Ok(())
+}
+
+#[test]
+fn malformed_inputs() -> Result<(), Box<dyn std::error::Error>> {
+    let tempdir = TempDir::new().unwrap();
+    let sizes = tempdir.path().join("bad.chr.sizes");
+    std::fs::write(&sizes, "I\t100\nII\tabc\n")?;
+    let yaml = tempdir.path().join("bad.yml");
+    std::fs::write(&yaml, "---\nI: [1, 2]\n")?;
+
+    // file and line number
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("genome").arg(&sizes);
+    cmd.assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "bad.chr.sizes:2: Invalid size abc",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("combine").arg(&yaml);
+    cmd.assert().code(4).stderr(predicate::str::contains(
+        "bad.yml: Runlist of I is not a string",
+    ));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("span")
+        .arg("tests/resources/brca2.yml")
+        .arg("--op")
+        .arg("foo");
+    cmd.assert().code(2).stderr(predicate::str::similar(
+        "intspan: Invalid IntSpan Op: foo\n",
+    ));
+
+    tempdir.close()?;
+    Ok(())
+}
+
 #[test]
 fn command_split() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -193,6 +265,47 @@ fn command_stat_all() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn command_stat_order() -> Result<(), Box<dyn std::error::Error>> {
+    let chrs_in = |order: &str| -> Vec<String> {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("stat")
+            .arg("tests/resources/S288c.chr.sizes")
+            .arg("tests/resources/intergenic.yml")
+            .arg("--order")
+            .arg(order)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').next().unwrap().to_string())
+            .collect()
+    };
+
+    let chrs = chrs_in("lexical");
+    assert_eq!(chrs[..5], ["I", "II", "III", "IV", "IX"]);
+    assert_eq!(chrs.last().unwrap(), "all");
+
+    let chrs = chrs_in("roman");
+    assert_eq!(chrs[..5], ["I", "II", "III", "IV", "V"]);
+    assert_eq!(chrs[15..], ["XVI", "all"]);
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("stat")
+        .arg("tests/resources/S288c.chr.sizes")
+        .arg("tests/resources/intergenic.yml")
+        .arg("--order")
+        .arg("romans");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid chromosome order: romans"));
+
+    Ok(())
+}
+
 #[test]
 fn command_statop() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -272,6 +385,29 @@ fn command_statop_invalid() -> Result<(), Box<dyn std::error::Error>> {
         .failure()
         .stderr(predicate::str::contains("Invalid IntSpan Op"));
 
+    // before any input is read
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("span")
+        .arg("stdin")
+        .arg("--op")
+        .arg("invalid")
+        .with_stdin()
+        .buffer("---\n{}\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid IntSpan Op"));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("flank")
+        .arg("stdin")
+        .arg("--op")
+        .arg("invalid")
+        .with_stdin()
+        .buffer("")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid Range Op"));
+
     Ok(())
 }
 
@@ -407,6 +543,135 @@ fn command_span_fill() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn command_span_flank() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("span")
+        .arg("tests/resources/I.yml")
+        .arg("--op")
+        .arg("flank")
+        .arg("--left")
+        .arg("100")
+        .arg("--right")
+        .arg("10")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 2);
+    assert!(
+        stdout.contains("13644-13743,17134-17143,19943-20042"),
+        "flanks"
+    );
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("span")
+        .arg("tests/resources/I.yml")
+        .arg("--op")
+        .arg("slop")
+        .arg("-n")
+        .arg("20000")
+        .arg("--sizes")
+        .arg("tests/resources/S288c.chr.sizes")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("I: 1-91785"), "bounded");
+
+    Ok(())
+}
+
+#[test]
+fn command_span_filter() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("span")
+        .arg("tests/resources/I.II.yml")
+        .arg("--op")
+        .arg("keep-largest")
+        .arg("-n")
+        .arg("2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("13744-17133,69526-71785"), "I");
+    assert!(stdout.contains("21294-22075,44919-45977"), "II");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("span")
+        .arg("tests/resources/I.II.yml")
+        .arg("--op")
+        .arg("size-range")
+        .arg("--min")
+        .arg("700")
+        .arg("--max")
+        .arg("800")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("I: \"-\""), "none in I");
+    assert!(stdout.contains("21294-22075,71128-71865"), "II");
+
+    Ok(())
+}
+
+#[test]
+fn command_flank() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("flank")
+        .arg("tests/resources/S288c.ranges")
+        .arg("--up")
+        .arg("100")
+        .arg("--down")
+        .arg("10")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 11);
+    assert!(stdout.contains("I(+):1-89\nI(+):151-160"), "clamped to 1");
+    assert!(
+        stdout.contains("S288c.I(-):201-300\nS288c.I(-):180-189"),
+        "minus strand"
+    );
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("flank")
+        .arg("tests/resources/S288c.ranges")
+        .arg("--op")
+        .arg("slop")
+        .arg("--up")
+        .arg("1000000")
+        .arg("--sizes")
+        .arg("tests/resources/S288c.chr.sizes")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 6);
+    assert!(stdout.contains("S288c.I(-):190-230218"), "bounded");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("flank")
+        .arg("tests/resources/S288c.ranges")
+        .arg("--op")
+        .arg("slop")
+        .arg("--up=-50");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("should not be negative"));
+
+    Ok(())
+}
+
 #[test]
 fn command_span_trim() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -502,6 +767,35 @@ fn command_cover() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn command_cover_overflow() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("cover")
+        .arg("stdin")
+        .with_stdin()
+        .buffer("I:1-100\nI:1-2147483647\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1-100"))
+        .stderr(predicate::str::contains(
+            "Skipped stdin:2: Coordinate overflow: I:1-2147483647",
+        ));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("cover")
+        .arg("stdin")
+        .arg("--strict")
+        .with_stdin()
+        .buffer("I:1-100\nI:1-2147483647\n")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "stdin:2: Coordinate overflow: I:1-2147483647",
+        ));
+
+    Ok(())
+}
+
 #[test]
 fn command_cover_c2() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -566,7 +860,7 @@ fn command_gff_merge() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--tag")
         .arg("CDS")
         .arg("-o")
-        .arg(&tempdir.path().join("cds.yml"))
+        .arg(tempdir.path().join("cds.yml"))
         .assert()
         .success()
         .stdout(predicate::str::is_empty());
@@ -577,7 +871,7 @@ fn command_gff_merge() -> Result<(), Box<dyn std::error::Error>> {
     cmd.arg("gff")
         .arg("tests/resources/NC_007942.rm.gff")
         .arg("-o")
-        .arg(&tempdir.path().join("repeat.yml"))
+        .arg(tempdir.path().join("repeat.yml"))
         .assert()
         .success()
         .stdout(predicate::str::is_empty());
@@ -587,8 +881,8 @@ fn command_gff_merge() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
     let output = cmd
         .arg("merge")
-        .arg(&tempdir.path().join("cds.yml"))
-        .arg(&tempdir.path().join("repeat.yml"))
+        .arg(tempdir.path().join("cds.yml"))
+        .arg(tempdir.path().join("repeat.yml"))
         .output()
         .unwrap();
     let stdout = String::from_utf8(output.stdout).unwrap();
@@ -617,6 +911,73 @@ fn command_convert() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn command_convert_bin() -> Result<(), Box<dyn std::error::Error>> {
+    let tempdir = TempDir::new().unwrap();
+    let bin = tempdir.path().join("intergenic.bin");
+    let bin_m = tempdir.path().join("I.II.bin");
+
+    for (infile, outfile) in &[
+        ("tests/resources/intergenic.yml", &bin),
+        ("tests/resources/I.II.yml", &bin_m),
+    ] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("convert")
+            .arg(infile)
+            .arg("--to")
+            .arg("bin")
+            .arg("-o")
+            .arg(outfile)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+    }
+    assert!(std::fs::read(&bin)?.starts_with(b"ISPB"));
+
+    // the same results as from YAML
+    let stat = |infile: &str| {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let output = cmd
+            .arg("stat")
+            .arg("tests/resources/S288c.chr.sizes")
+            .arg(infile)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(
+        stat(bin.to_str().unwrap()),
+        stat("tests/resources/intergenic.yml")
+    );
+    assert_eq!(
+        stat(bin_m.to_str().unwrap()),
+        stat("tests/resources/I.II.yml")
+    );
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd.arg("split").arg(&bin_m).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 4);
+    assert!(stdout.contains("21294-22075,23537-24097"));
+
+    Ok(())
+}
+
+#[test]
+fn command_convert_bin_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("convert")
+        .arg("tests/resources/I.yml")
+        .arg("tests/resources/II.yml")
+        .arg("--to")
+        .arg("bin")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("takes a single input file"));
+
+    Ok(())
+}
+
 #[test]
 fn command_range() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -681,3 +1042,366 @@ fn command_range_invalid() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn command_range_strict() -> Result<(), Box<dyn std::error::Error>> {
+    let tempdir = TempDir::new().unwrap();
+    let ranges = tempdir.path().join("bad.ranges");
+    std::fs::write(&ranges, "#ranges\nII:21294-22075\nII:500-100\nII(x):1-10\n")?;
+
+    // skipped with a line number
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("range")
+        .arg("tests/resources/intergenic.yml")
+        .arg(&ranges);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::similar("II:21294-22075\n"))
+        .stderr(predicate::str::contains(
+            "bad.ranges:3: Inverted coordinates: II:500-100",
+        ))
+        .stderr(predicate::str::contains(
+            "bad.ranges:4: Invalid strand: II(x):1-10",
+        ))
+        .stderr(predicate::str::contains("#ranges").not());
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("range")
+        .arg("tests/resources/intergenic.yml")
+        .arg(&ranges)
+        .arg("--strict");
+    cmd.assert().code(4).stderr(predicate::str::contains(
+        "bad.ranges:3: Inverted coordinates: II:500-100",
+    ));
+
+    tempdir.close()?;
+    Ok(())
+}
+
+#[test]
+fn command_window() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("window")
+        .arg("tests/resources/brca2.yml")
+        .arg("--size")
+        .arg("10000")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 9);
+    assert!(stdout.contains("13:32316461-32326282"), "first window");
+    assert!(stdout.contains("13:32396898-32398770"), "last window");
+
+    Ok(())
+}
+
+#[test]
+fn command_window_members() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("window")
+        .arg("tests/resources/brca2.yml")
+        .arg("--size")
+        .arg("1000")
+        .arg("--step")
+        .arg("500")
+        .arg("--members")
+        .arg("--yaml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.contains("\"13:32316461-32332478\":"),
+        "named by range"
+    );
+    assert!(stdout.contains("\"13\": \"32332479-32333387,32336265-32336355\""));
+
+    Ok(())
+}
+
+#[test]
+fn command_window_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("window")
+        .arg("tests/resources/brca2.yml")
+        .arg("--size")
+        .arg("0");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("should be positive"));
+
+    Ok(())
+}
+
+#[test]
+fn command_shuffle() -> Result<(), Box<dyn std::error::Error>> {
+    let run = || {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        let output = cmd
+            .arg("shuffle")
+            .arg("tests/resources/S288c.chr.sizes")
+            .arg("tests/resources/S288c.ranges")
+            .arg("--mask")
+            .arg("tests/resources/intergenic.yml")
+            .arg("--seed")
+            .arg("42")
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let stdout = run();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 6);
+    assert_eq!(stdout, run(), "seeded");
+
+    let mask: std::collections::BTreeMap<String, intspan::IntSpan> =
+        intspan::read_set("tests/resources/intergenic.yml").unwrap();
+    let expected = ["I:", "I(+):", "S288c.I(-):", "II:", "II:", "S288c.I(-):"];
+    let lengths = [100, 61, 11, 782, 561, 11];
+    for (i, line) in stdout.lines().enumerate() {
+        assert!(line.starts_with(expected[i]), "name, chr and strand");
+
+        let range = intspan::Range::from_str(line);
+        assert_eq!(range.end() - range.start() + 1, lengths[i], "length");
+        assert!(mask[range.chr()].superset(&range.intspan()), "within mask");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn command_shuffle_no_room() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("shuffle")
+        .arg("tests/resources/S288c.chr.sizes")
+        .arg("stdin")
+        .arg("--seed")
+        .arg("1")
+        .with_stdin()
+        .buffer("I:1-300000\nXX:1-10\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("No room for I:1-300000"))
+        .stderr(predicate::str::contains("No room for XX:1-10"));
+
+    Ok(())
+}
+
+#[test]
+fn command_shuffle_others() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("shuffle")
+        .arg("tests/resources/S288c.chr.sizes")
+        .arg("stdin")
+        .arg("--seed")
+        .arg("1")
+        .with_stdin()
+        .buffer("S288c.I(+):100-200 gene=YAL069W dubious ORF\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("S288c.I(+):"))
+        .stdout(predicate::str::ends_with(" gene=YAL069W dubious ORF\n"));
+
+    Ok(())
+}
+
+#[test]
+fn command_similarity() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("similarity")
+        .arg("tests/resources/Atha.yml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 6);
+    assert!(stdout.starts_with("name\tAT1G01010.1\t"), "header");
+    assert!(
+        stdout.contains("AT1G01020.1\t0.0000\t1.0000\t0.5265\t"),
+        "jaccard"
+    );
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("similarity")
+        .arg("tests/resources/Atha.yml")
+        .arg("--metric")
+        .arg("distance")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("AT1G01020.2\t2773\t840\t0\t"), "distance");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("similarity")
+        .arg("tests/resources/intergenic.yml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "name\tintergenic\nintergenic\t1.0000\n", "single");
+
+    Ok(())
+}
+
+#[test]
+fn command_nway() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("nway")
+        .arg("tests/resources/Atha.yml")
+        .arg("--op")
+        .arg("at-least")
+        .arg("-k")
+        .arg("2")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 3);
+    assert!(stdout.contains("6790-7069,7157-7232"), "shared by 2 names");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("nway")
+        .arg("tests/resources/Atha.yml")
+        .arg("--op")
+        .arg("exclusive")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.contains("AT1G01020.2:\n  \"1\": 7233-7383"),
+        "exclusive"
+    );
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("nway")
+        .arg("tests/resources/intergenic.yml")
+        .arg("tests/resources/repeat.yml")
+        .arg("--op")
+        .arg("upset")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.starts_with("chr\tintergenic\trepeat\tsize\n"),
+        "labels"
+    );
+    assert!(stdout.contains("II\t0\t1\t635\n"), "upset");
+
+    Ok(())
+}
+
+#[test]
+fn command_nway_invalid_k() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("nway")
+        .arg("tests/resources/Atha.yml")
+        .arg("--op")
+        .arg("at-least")
+        .arg("-k")
+        .arg("6");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("between 1 and 5"));
+
+    Ok(())
+}
+
+#[test]
+fn command_nway_duplicate_label() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("nway")
+        .arg("tests/resources/intergenic.yml")
+        .arg("tests/resources/intergenic.yml")
+        .arg("--op")
+        .arg("exclusive");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Duplicate set label: intergenic"));
+
+    Ok(())
+}
+
+#[test]
+fn command_eval() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("eval")
+        .arg("\"AT1G01020.1\" & \"AT1G01020.2\".pad(10) | \"AT1G01010.1\".cover()")
+        .arg("tests/resources/Atha.yml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 2);
+    assert!(stdout.contains("3631-5899,6780-7069"), "evaluated");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .arg("eval")
+        .arg("intergenic - repeat")
+        .arg("tests/resources/intergenic.yml")
+        .arg("tests/resources/repeat.yml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.lines().collect::<Vec<_>>().len(), 17);
+    assert!(
+        stdout.contains("I: \"13744-17133,"),
+        "names from file stems"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn command_eval_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("eval")
+        .arg("intergenic & (repeat | )")
+        .arg("tests/resources/intergenic.yml");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unexpected `)` at column 24"))
+        .stderr(predicate::str::contains("\n                           ^\n"));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("eval")
+        .arg("intergenic & repeat.grow(5)")
+        .arg("tests/resources/intergenic.yml");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unknown span op `grow` at column 21",
+    ));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("eval")
+        .arg("intergenic & repeat")
+        .arg("tests/resources/intergenic.yml");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unknown name `repeat` at column 14",
+    ));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("eval")
+        .arg("intergenic")
+        .arg("tests/resources/intergenic.yml")
+        .arg("tests/resources/intergenic.yml");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Duplicate set name: intergenic"));
+
+    Ok(())
+}
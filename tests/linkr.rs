@@ -14,6 +14,30 @@ fn command_invalid() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn file_unreadable() -> Result<(), Box<dyn std::error::Error>> {
+    for subcommand in &["sort", "merge", "filter", "clean", "circos"] {
+        let mut cmd = Command::cargo_bin("linkr")?;
+        cmd.arg(subcommand).arg("tests/resources");
+        cmd.assert()
+            .code(3)
+            .stderr(predicate::str::starts_with("linkr: tests/resources: "));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn file_doesnt_exist() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("linkr")?;
+    cmd.arg("sort").arg("tests/file/doesnt/exist");
+    cmd.assert().code(3).stderr(predicate::str::starts_with(
+        "linkr: tests/file/doesnt/exist: ",
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn command_circos() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("linkr")?;
@@ -64,6 +88,22 @@ fn command_sort() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn command_sort_strict() -> Result<(), Box<dyn std::error::Error>> {
+    let tempdir = TempDir::new().unwrap();
+    let links = tempdir.path().join("bad.links.tsv");
+    std::fs::write(&links, "II:1-10\tII:101-110\t+\nII:10-1\tII:101-110\t+\n")?;
+
+    let mut cmd = Command::cargo_bin("linkr")?;
+    cmd.arg("sort").arg(&links).arg("--strict");
+    cmd.assert().code(4).stderr(predicate::str::contains(
+        "bad.links.tsv:2: Inverted coordinates: II:10-1",
+    ));
+
+    tempdir.close()?;
+    Ok(())
+}
+
 #[test]
 fn command_merge() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("linkr")?;